@@ -1,6 +1,9 @@
 use core::panic;
 
-use aoc_utils::grid::{Grid, Point, UP_RIGHT_DOWN_LEFT};
+use advent_of_code::{
+    grid::{Grid, Point, UP_RIGHT_DOWN_LEFT},
+    visualize::{self, Colour, Frame},
+};
 
 advent_of_code::solution!(6);
 
@@ -47,22 +50,38 @@ pub fn part_one(input: &str) -> Option<usize> {
     while let Some(s) = state {
         hit += (seen[s.0] == false) as usize;
         seen[s.0] = true;
+        visualize::frame(|| {
+            lab_frame(&grid, s, |p| if seen[p] { 'X' } else { '.' })
+                .caption(format!("{hit} positions visited"))
+        });
         state = step(&grid, s);
     }
 
-    // let _view = Grid::new_with_dimensions(seen.dimension(), |x| x).map(|&x| {
-    //     if grid[x] == b'#' {
-    //         b'#'
-    //     } else if seen[x] {
-    //         b'X'
-    //     } else {
-    //         b'.'
-    //     }
-    // });
+    Some(hit)
+}
 
-    // eprintln!("{}", view.display());
+const GUARD: [char; 4] = ['^', '>', 'v', '<'];
 
-    Some(hit)
+/// The lab with the guard facing its direction, every other free cell is drawn by `mark`.
+fn lab_frame(
+    grid: &Grid<u8>,
+    (player, direction): (Point, usize),
+    mark: impl Fn(Point) -> char,
+) -> Frame {
+    let view = Grid::new_with_dimensions(grid.dimension(), |p| {
+        if p == player {
+            GUARD[direction]
+        } else if grid[p] == b'#' {
+            '#'
+        } else {
+            mark(p)
+        }
+    });
+    Frame::chars(&view)
+        .paint('#', Colour::Grey)
+        .paint('X', Colour::Yellow)
+        .paint('O', Colour::Red)
+        .highlight(player)
 }
 
 fn find_cycle(grid: &Grid<u8>, state: (Point, usize)) -> Option<(Point, usize)> {
@@ -110,24 +129,19 @@ pub fn part_two(input: &str) -> Option<usize> {
             }
         }
         seen[s.0] |= 1;
+        visualize::frame(|| {
+            lab_frame(&grid, s, |p| match seen[p] {
+                x if x & 0b10 > 0 => 'O',
+                x if x & 0b1 > 0 => 'X',
+                _ => '.',
+            })
+            .caption(format!("{cycles} obstructions cause a loop"))
+        });
         // next step
         prev = state;
         state = step(&grid, s);
     }
 
-    // let view = Grid::new_with_dimensions(grid.dimension(), |x| x).map(|&x| {
-    //     if grid[x] == b'#' {
-    //         b'#'
-    //     } else if seen[x] & 0b10 > 0 {
-    //         b'O'
-    //     } else if seen[x] & 0b1 > 0 {
-    //         b'x'
-    //     } else {
-    //         b'.'
-    //     }
-    // });
-    // eprintln!("{}", view.display());
-
     Some(cycles)
 }
 
@@ -1,82 +1,44 @@
-advent_of_code::solution!(7);
+use advent_of_code::equation::{Equation, Operator, ADD, CONCAT, MUL};
+use advent_of_code::parse::{self, Span};
+use advent_of_code::template::runner::is_tracing;
 
-fn add(a: usize, b: usize) -> usize {
-    a + b
-}
+advent_of_code::solution!(7);
 
-fn mul(a: usize, b: usize) -> usize {
-    a * b
-}
-fn conc(a: usize, b: usize) -> usize {
-    if b == 0 {
-        return a * 10;
-    }
-    let digits = b.ilog10() + 1;
-    a * (10usize.pow(digits)) + b
+fn parse_line(line: Span) -> Result<Equation, parse::Error> {
+    let (goal, nums) = line.key_value(":")?;
+    let nums = nums.words().map(Span::parse).collect::<Result<_, _>>()?;
+    Ok(Equation {
+        goal: goal.parse()?,
+        nums,
+    })
 }
 
-fn consider_eval_r(
-    operators: &[fn(usize, usize) -> usize],
-    goal: usize,
-    current: usize,
-    nums: &[usize],
-) -> bool {
-    if nums.len() == 0 {
-        return current == goal;
-    }
-    if current > goal {
-        return false;
-    }
-    for op in operators {
-        let folded = op(current, nums[0]);
-        if consider_eval_r(operators, goal, folded, &nums[1..]) {
-            // eprint!("{} ", &"+*"[i..i + 1]);
-            return true;
+fn analyse(input: &str, operators: &[Operator]) -> Option<u64> {
+    let equations = parse::report(parse::lines(input.trim(), parse_line))?;
+    let mut explored = 0;
+    let mut total = 0;
+    for equation in equations {
+        let outcome = equation.solve(operators);
+        explored += outcome.explored;
+        if let Some(found) = outcome.operators {
+            if is_tracing() {
+                println!("{}", equation.show(&found));
+            }
+            total += equation.goal;
         }
     }
-    false
-}
-
-fn consider_eval(operators: &[fn(usize, usize) -> usize], goal: usize, nums: &[usize]) -> bool {
-    let r = consider_eval_r(operators, goal, nums[0], &nums[1..]);
-    r
-}
-
-fn parse_line(input: &str) -> (usize, Vec<usize>) {
-    let mut i = input.split(": ");
-    let goal_s = i.next().expect("Expected goal; LHS of ':'");
-    let nums_s = i.next().expect("Expected numbers, RHS of ':'");
-    let goal: usize = goal_s
-        .trim()
-        .parse()
-        .expect("Expected goal string to be numerical");
-    let nums: Vec<usize> = nums_s
-        .trim()
-        .split_whitespace()
-        .map(|x| {
-            x.parse::<usize>()
-                .expect("Expected all RHS nums to be parsable")
-        })
-        .collect::<_>();
-    (goal, nums)
-}
-
-fn analyse(input: &str, operators: &[fn(usize, usize) -> usize]) -> usize {
-    input
-        .trim()
-        .split('\n')
-        .map(parse_line)
-        .filter(|(goal, nums)| consider_eval(operators, *goal, nums))
-        .map(|(goal, _nums)| goal)
-        .sum()
+    if is_tracing() {
+        println!("explored {explored} states");
+    }
+    Some(total)
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    Some(analyse(input, &[add, mul][..]))
+pub fn part_one(input: &str) -> Option<u64> {
+    analyse(input, &[ADD, MUL])
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    Some(analyse(input, &[add, mul, conc][..]))
+pub fn part_two(input: &str) -> Option<u64> {
+    analyse(input, &[ADD, MUL, CONCAT])
 }
 
 #[cfg(test)]
@@ -85,24 +47,30 @@ mod tests {
 
     #[test]
     fn test_evaluator() {
-        let shim = |(goal, nums): (usize, Vec<usize>)| consider_eval(&[add, mul][..], goal, &nums);
-        let r = |l| shim(parse_line(l));
+        let r = |l| {
+            let equation = parse_line(Span::new(l)).unwrap();
+            equation.solve(&[ADD, MUL]).operators.is_some()
+        };
         assert!(r("190: 10 19"));
-        println!();
         assert!(r("3267: 81 40 27"));
-        println!();
         assert!(r("13757372640: 4 21 8 6 854 4 8 9 2 66 5"));
         assert!(r("873: 8 838 9 9 1 9"));
         assert!(!r("21037: 9 7 18 13"));
         assert!(!r("80: 6 7 3 5 2"));
     }
 
+    #[test]
+    fn test_parse_error() {
+        let error = parse_line(Span::new("190: 10 l9")).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 9));
+    }
+
     #[test]
     fn test_concat() {
-        assert_eq!(conc(12, 16), 1216);
-        assert_eq!(conc(12, 6), 126);
-        assert_eq!(conc(12, 0), 120);
-        assert_eq!(conc(12, 10), 1210);
+        assert_eq!((CONCAT.apply)(12, 16), Some(1216));
+        assert_eq!((CONCAT.apply)(12, 6), Some(126));
+        assert_eq!((CONCAT.apply)(12, 0), Some(120));
+        assert_eq!((CONCAT.apply)(12, 10), Some(1210));
     }
 
     #[test]
@@ -1,6 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use aoc_utils::grid::{Grid, Point};
+use advent_of_code::{
+    grid::{Grid, Point},
+    lattice::{self, Ratio},
+    visualize::{self, Colour, Frame},
+};
 use itertools::Itertools;
 use lina::point2;
 
@@ -16,12 +20,9 @@ pub fn read_antennas(input: &str) -> (Grid<u8>, HashMap<u8, Vec<Point>>) {
             if c == b'.' || c == b'#' {
                 return;
             }
-            if !antennae.contains_key(&c) {
-                antennae.insert(c, Vec::new());
-            }
             antennae
-                .get_mut(&c)
-                .unwrap()
+                .entry(c)
+                .or_default()
                 .push(point2(x as i32, y as i32))
         })
     });
@@ -29,78 +30,58 @@ pub fn read_antennas(input: &str) -> (Grid<u8>, HashMap<u8, Vec<Point>>) {
     (grid, antennae)
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let (grid, antennae) = read_antennas(input);
+/// One frame per frequency with its antennas highlighted, then all antinodes.
+fn antinode_frames(grid: &Grid<u8>, antinodes: &HashMap<u8, HashSet<Point>>) {
+    let view = |points: &HashSet<Point>| {
+        Grid::new_with_dimensions(grid.dimension(), |p| match grid[p] {
+            b'.' if points.contains(&p) => '#',
+            c => c as char,
+        })
+    };
 
-    Some(
-        antennae
-            .into_iter()
-            .map(|(_label, antennae_group)| {
-                let pairs: Vec<(Point, Point)> = antennae_group
-                    .iter()
-                    .enumerate()
-                    .flat_map(|(i, a)| antennae_group[i..].iter().map(|b| (*a, *b)))
-                    .collect::<Vec<_>>();
-                pairs
-                    .iter()
-                    .filter(|(a, b)| a != b)
-                    // .inspect(|(a, b)| {
-                    //     eprintln!(
-                    //         "{}: ({:>2}, {:>2}) - ({:>2}, {:>2})",
-                    //         label as char,
-                    //         a.x,
-                    //         a.y,
-                    //         b.x,
-                    //         b.y + 1
-                    //     )
-                    // })
-                    .map(|&(a, b)| {
-                        let diff = a - b;
-                        let n1 = a + diff;
-                        let n2 = b - diff;
-                        [n1, n2]
-                    })
-                    .flatten()
-                    .collect::<Vec<_>>()
-            })
-            .flatten()
-            .filter(|&x| grid.contains(x))
-            .unique()
-            // .inspect(|x| eprintln!("({:>2}, {:>2})\t({:>2}, {:>2})", x.x, x.y, x.x + 1, x.y + 1))
-            .count(),
-    )
+    for (&frequency, points) in antinodes.iter().sorted_by_key(|(&frequency, _)| frequency) {
+        visualize::frame(|| {
+            let antennas = grid.iter_coordinates().filter(|&p| grid[p] == frequency);
+            Frame::chars(&view(points))
+                .paint('#', Colour::Yellow)
+                .colour_all(points.iter().copied(), Colour::Yellow)
+                .highlight_all(antennas)
+                .caption(format!("antinodes of {}", frequency as char))
+        });
+    }
+    visualize::frame(|| {
+        let all = antinodes.values().flatten().copied().collect();
+        Frame::chars(&view(&all))
+            .paint('#', Colour::Yellow)
+            .colour_all(all.iter().copied(), Colour::Yellow)
+            .caption("all antinodes")
+    });
+}
+
+fn count(antinodes: &HashMap<u8, HashSet<Point>>) -> usize {
+    antinodes.values().flatten().unique().count()
 }
 
-fn add_group(antinodes: &mut Grid<bool>, antennae: &[Point]) {
-    let pairs: Vec<(Point, Point)> = antennae
-        .iter()
-        .enumerate()
-        .flat_map(|(i, a)| antennae[i + 1..].iter().map(|b| (*a, *b)))
-        .collect::<Vec<_>>();
-
-    for (a, b) in pairs {
-        // dbg!(a, b);
-        let diff = a - b;
-        let mut node = a;
-        while antinodes.contains(node) {
-            antinodes[node] = true;
-            node += diff;
-        }
-        node = b;
-        while antinodes.contains(node) {
-            antinodes[node] = true;
-            node -= diff;
-        }
+pub fn part_one(input: &str) -> Option<usize> {
+    let (grid, antennae) = read_antennas(input);
+    let antinodes = lattice::antinodes(&antennae, |a, b| {
+        lattice::at_ratio(a, b, Ratio::DOUBLE, false)
+            .into_iter()
+            .filter(|&p| grid.contains(p))
+    });
+    if visualize::ENABLED {
+        antinode_frames(&grid, &antinodes);
     }
+    Some(count(&antinodes))
 }
+
 pub fn part_two(input: &str) -> Option<usize> {
     let (grid, antennae) = read_antennas(input);
-
-    let mut antinode = Grid::new_with_dimensions_uniform(grid.dimension(), false);
-    for (_label, antennae_group) in antennae.iter() {
-        add_group(&mut antinode, &antennae_group);
+    let antinodes = lattice::antinodes(&antennae, |a, b| lattice::line(a, b, |p| grid.contains(p)));
+    if visualize::ENABLED {
+        antinode_frames(&grid, &antinodes);
     }
-    Some(antinode.0.concat().iter().map(|&x| x as usize).sum())
+    Some(count(&antinodes))
 }
 
 #[cfg(test)]
@@ -123,4 +104,11 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(34));
     }
+
+    #[test]
+    fn test_non_primitive_difference() {
+        // the antennas are (4, 2) apart, so (2, 1) and (6, 3) are in line with them too.
+        let input = "0.......\n........\n....0...\n........\n........\n";
+        assert_eq!(part_two(input), Some(4));
+    }
 }
@@ -1,142 +1,51 @@
-use std::collections::HashSet;
-
-use aoc_utils::grid::{Grid, Point, UP_RIGHT_DOWN_LEFT};
-use lina::point2;
+use advent_of_code::parse;
+use advent_of_code::template::runner::is_tracing;
+use advent_of_code::trails::{Analysis, Rule, TopoMap};
+use advent_of_code::visualize;
 
 advent_of_code::solution!(10);
 
-struct Cell {
-    height: u8,
-    score: usize,
-}
-
-fn parse(input: &str) -> Grid<Cell> {
-    Grid::read(input, |c| Cell {
-        height: c.to_digit(10).expect("expected only '0'~'9'") as u8,
-        score: 0,
-    })
-}
-
-fn dfs(grid: &mut Grid<Cell>, v: Point, visited: &mut HashSet<Point>) {
-    visited.insert(v);
-    // if grid[v].height == 9 {
-    //     grid[v].score = Some(1);
-    //     return 1;
-    // }
-    // if let Some(score) = grid[v].score {
-    //     return score;
-    // }
-    // let mut score = 0;
-    for dir in UP_RIGHT_DOWN_LEFT {
-        let next = v + dir;
-        if !grid.contains(next) {
-            continue;
-        }
-        if grid[next].height + 1 != grid[v].height {
-            continue;
-        }
-        if visited.contains(&next) {
-            continue;
-        }
-        dfs(grid, next, visited);
-        // score += dfs(grid, next, visited);
+/// Print the best rated trailhead and one of its trails.
+fn trace(analysis: &Analysis) {
+    let Some(best) = analysis
+        .trailheads()
+        .max_by_key(|&p| (analysis.ratings[p], -p.y, -p.x))
+    else {
+        return;
+    };
+    println!(
+        "{} trailheads, best at ({}, {}) with score {} and rating {}",
+        analysis.trailheads().count(),
+        best.x,
+        best.y,
+        analysis.scores[best],
+        analysis.ratings[best]
+    );
+    if let Some(trail) = analysis.trail(best, 0) {
+        let steps: Vec<String> = trail
+            .iter()
+            .map(|p| format!("({}, {})", p.x, p.y))
+            .collect();
+        println!("  {}", steps.join(" -> "));
     }
-    grid[v].score += 1;
-    // score
 }
 
-fn dfs_2(grid: &mut Grid<Cell>, v: Point) {
-    for dir in UP_RIGHT_DOWN_LEFT {
-        let next = v + dir;
-        if !grid.contains(next) {
-            continue;
-        }
-        if grid[next].height + 1 != grid[v].height {
-            continue;
-        }
-        dfs_2(grid, next);
+fn analyse(input: &str) -> Option<(usize, u64)> {
+    let map = parse::report(TopoMap::parse(input))?;
+    let analysis = map.analyse(Rule::PUZZLE);
+    visualize::frame(|| analysis.heat_map());
+    if is_tracing() {
+        trace(&analysis);
     }
-    grid[v].score += 1;
-}
-
-fn search(grid: &mut Grid<Cell>, v: Point) {
-    let mut visited = HashSet::new();
-    dfs(grid, v, &mut visited)
-}
-
-fn p(grid: &Grid<Cell>) {
-    println!(
-        "{}\n",
-        grid.map(|x| {
-            // if let Some(s) = x.score {
-            //     if s > 0 {
-            //         char::from_digit(s as u32, 16).unwrap() as u8
-            //     } else {
-            //         b'-'
-            //     }
-            // } else {
-            //     b'.'
-            // }
-            if x.score > 0 {
-                char::from_digit(x.score as u32, 16).unwrap() as u8
-            } else {
-                b'.'
-            }
-        })
-        .display()
-    );
+    Some((analysis.score(), analysis.rating()))
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let mut grid = parse(input);
-    let dim = grid.dimension();
-    (0..dim.y)
-        .map(|y| (0..dim.x).map(move |x| point2(x as i32, y as i32)))
-        .flatten()
-        .for_each(|point| {
-            if grid[point].height == 9 {
-                // p(&grid);
-                search(&mut grid, point)
-            }
-        });
-
-    let res = score(&grid);
-
-    // dbg!(grid.map(|x| x.score).0);
-    // p(&grid);
-    // println!();
-
-    Some(res)
+    analyse(input).map(|(score, _)| score)
 }
 
-fn score(grid: &Grid<Cell>) -> usize {
-    grid.0
-        .iter()
-        .map(|r| r.iter().map(|x| x.score * ((x.height == 0) as usize)))
-        .flatten()
-        .sum()
-}
-
-pub fn part_two(input: &str) -> Option<usize> {
-    let mut grid = parse(input);
-    let dim = grid.dimension();
-    (0..dim.y)
-        .map(|y| (0..dim.x).map(move |x| point2(x as i32, y as i32)))
-        .flatten()
-        .for_each(|point| {
-            if grid[point].height == 9 {
-                // p(&grid);
-                dfs_2(&mut grid, point)
-            }
-        });
-
-    let res = score(&grid);
-
-    // dbg!(grid.map(|x| x.score).0);
-    // p(&grid);
-    // println!();
-
-    Some(res)
+pub fn part_two(input: &str) -> Option<u64> {
+    analyse(input).map(|(_, rating)| rating)
 }
 
 #[cfg(test)]
@@ -1,9 +1,10 @@
-use aoc_utils::parse;
+use advent_of_code::linear::{solve2, Diophantine};
+use advent_of_code::parse::{self, Span};
 use lina::{point2, vec2, Point2, Vec2};
 
 advent_of_code::solution!(13);
 
-type I = i128;
+type I = advent_of_code::linear::Int;
 
 const PART_2_OFFSET: I = 10000000000000;
 
@@ -15,81 +16,54 @@ struct Machine {
 }
 
 impl Machine {
-    pub fn parse(input: &str) -> Option<Self> {
-        let mut nums = parse::nums_positive::<I>(input).into_iter();
-        Some(Machine {
-            a: vec2(nums.next()?, nums.next()?),
-            b: vec2(nums.next()?, nums.next()?),
-            prize: point2(nums.next()?, nums.next()?),
+    pub fn parse(input: Span) -> Result<Self, parse::Error> {
+        let [a, b, prize] = input.trim().lines_exact()?;
+        Ok(Machine {
+            a: Self::parse_line(a, "Button A")?.to_vec(),
+            b: Self::parse_line(b, "Button B")?.to_vec(),
+            prize: Self::parse_line(prize, "Prize")?,
         })
     }
-    fn maximise(&self) -> Option<I> {
-        let mut best_score: Option<I> = None;
-        for b_count in (0 as I).. {
-            if b_count * self.b.x > self.prize.x || b_count * self.b.y > self.prize.y {
-                break;
-            }
-            let req = self.prize - (b_count * self.b);
-            // dbg!(b_count, req, req.x % self.a.x == 0, req.y % self.a.y == 0);
-            if req.x % self.a.x == 0
-                && req.y % self.a.y == 0
-                && req.x / self.a.x == req.y / self.a.y
-            {
-                let a_count = req.x / self.a.x;
-                let cost = A_COST * a_count + B_COST * b_count;
-                assert_eq!(a_count * self.a + b_count * self.b, self.prize.to_vec());
-                if best_score.map(|bs| cost < bs).unwrap_or(true) {
-                    assert!(
-                        best_score.is_none(),
-                        "There should only ever be one way of producing a vector from the basis"
-                    );
-                    best_score = Some(cost);
-                }
-            }
-        }
-        best_score
-    }
-
-    #[allow(non_snake_case)]
-    fn maximise_fast(&self) -> Option<I> {
-        /*
-         A(x1, y1) + B(x2, y2) = (X, Y)
-         A.x1 + B.y1 = X (1)
-         A.x2 + B.y2 = Y (2)
-
-         x1.(2) - y1.(1):
-         A.x1.y1 + B.x1.y2 - A.x1.y1 - B.x2.y1 = Y.x1 - X.y1
-
-         B (x1.y2 - x2.y1) = Y.x1 - X.y1
 
-         Also shows us that there are one or zero solutions. Which shold have been obvious because *A* and *B* form a basis.
-        */
-
-        let x1 = self.a.x;
-        let y1 = self.a.y;
-        let x2 = self.b.x;
-        let y2 = self.b.y;
-
-        let X = self.prize.x;
-        let Y = self.prize.y;
-
-        assert!(self.a != self.b);
-        let rhs = Y * x1 - X * y1;
-        let denom = y2 * x1 - x2 * y1;
+    /// A line like `Button A: X+94, Y+34` with the expected `label`.
+    fn parse_line(line: Span, label: &str) -> Result<Point2<I>, parse::Error> {
+        let (key, value) = line.trim().key_value(":")?;
+        key.expect(label)?;
+        let [x, y] = value.exactly(value.nums_positive()?, "numbers")?;
+        Ok(point2(x, y))
+    }
 
-        let b = rhs / denom;
-        // dbg!(rhs as f64 / denom as f64, rhs, denom, &self);
-        if rhs % denom != 0 {
+    /// The fewest tokens that win the prize, or `None` if it cannot be won. If the buttons move in the same
+    /// direction there can be many ways to win, and the cheapest is found along the line of them.
+    fn cheapest(&self, costs: Costs) -> Option<I> {
+        let (a, b) = match solve2(
+            [[self.a.x, self.b.x], [self.a.y, self.b.y]],
+            self.prize.into(),
+        ) {
+            Some([a, b]) => (a.to_integer()?, b.to_integer()?),
+            None => self.cheapest_dependent(costs)?,
+        };
+        if a < 0 || b < 0 {
             return None;
         }
-        let a = (X - b * x2) / x1;
-        if (X - b * x2) % x1 != 0 {
+        assert_eq!(a * self.a + b * self.b, self.prize.to_vec());
+        Some(a * costs.a + b * costs.b)
+    }
+
+    /// Presses for buttons that are multiples of each other. The prize must lie on their line, and then a
+    /// coordinate in which they move decides the presses.
+    fn cheapest_dependent(&self, costs: Costs) -> Option<(I, I)> {
+        let direction = if self.a == vec2(0, 0) { self.b } else { self.a };
+        let prize = self.prize.to_vec();
+        if direction.x * prize.y != direction.y * prize.x {
             return None;
         }
-
-        // dbg!(&self);
-        assert_eq!(a * self.a + b * self.b, self.prize.to_vec(),);
-        Some(a * A_COST + b * B_COST)
+        if direction == vec2(0, 0) {
+            return (prize == direction).then_some((0, 0));
+        }
+        let axis = usize::from(direction.x == 0);
+        let line = Diophantine::solve(self.a[axis], self.b[axis], prize[axis])?;
+        line.cheapest_non_negative(costs.a, costs.b)
     }
 
     fn part2(&self) -> Self {
@@ -101,57 +75,63 @@ impl Machine {
     }
 }
 
-const A_COST: I = 3;
-const B_COST: I = 1;
+/// Tokens per press of each button.
+#[derive(Clone, Copy, Debug)]
+struct Costs {
+    a: I,
+    b: I,
+}
+
+const PUZZLE_COSTS: Costs = Costs { a: 3, b: 1 };
 
 pub fn part_one(input: &str) -> Option<u64> {
-    solve(input, |x: &Machine| x.maximise_fast().unwrap_or(0) as u64)
+    solve(input, |x: &Machine| {
+        x.cheapest(PUZZLE_COSTS).unwrap_or(0) as u64
+    })
 }
 
 fn solve(input: &str, f: impl Fn(&Machine) -> u64) -> Option<u64> {
-    let Some(machines) = input
-        .split("\n\n")
-        .map(Machine::parse)
-        .collect::<Option<Vec<_>>>()
-    else {
-        panic!("All machines could not be parsed.");
-    };
+    let machines = parse::report(parse::sections(input, Machine::parse))?;
     let score = machines.iter().map(f).sum();
     Some(score)
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    solve(input, |x| x.part2().maximise_fast().unwrap_or(0) as u64)
+    solve(input, |x| {
+        x.part2().cheapest(PUZZLE_COSTS).unwrap_or(0) as u64
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn machine(input: &str) -> Machine {
+        Machine::parse(Span::new(input)).unwrap()
+    }
+
     #[test]
     fn test_machine() {
-        let m = Machine::parse(
-            "
-                    Button A: X+94, Y+34
-                    Button B: X+22, Y+67
-                    Prize: X=8400, Y=5400
-            ",
-        )
-        .unwrap();
-
-        assert_eq!(m.maximise(), Some(280));
-        assert_eq!(m.maximise_fast(), Some(280));
-        let m = Machine::parse(
-            "
-                Button A: X+56, Y+20
-                Button B: X+24, Y+48
-                Prize: X=1264, Y=11536
-            ",
-        )
-        .unwrap();
-
-        assert_eq!(m.maximise_fast(), None);
-        assert_eq!(m.maximise(), None);
+        let m = machine("Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400");
+        assert_eq!(m.cheapest(PUZZLE_COSTS), Some(280));
+        let m = machine("Button A: X+56, Y+20\nButton B: X+24, Y+48\nPrize: X=1264, Y=11536");
+        assert_eq!(m.cheapest(PUZZLE_COSTS), None);
+    }
+
+    #[test]
+    fn test_dependent_buttons() {
+        let m = machine("Button A: X+1, Y+1\nButton B: X+3, Y+3\nPrize: X=9, Y=9");
+        assert_eq!(m.cheapest(PUZZLE_COSTS), Some(3));
+        assert_eq!(m.cheapest(Costs { a: 1, b: 5 }), Some(9));
+
+        let m = machine("Button A: X+2, Y+0\nButton B: X+4, Y+0\nPrize: X=10, Y=0");
+        assert_eq!(m.cheapest(PUZZLE_COSTS), Some(5));
+        let m = machine("Button A: X+2, Y+2\nButton B: X+4, Y+4\nPrize: X=9, Y=9");
+        assert_eq!(m.cheapest(PUZZLE_COSTS), None);
+        let m = machine("Button A: X+1, Y+2\nButton B: X+2, Y+4\nPrize: X=4, Y=4");
+        assert_eq!(m.cheapest(PUZZLE_COSTS), None);
+        let m = machine("Button A: X+0, Y+0\nButton B: X+0, Y+5\nPrize: X=0, Y=10");
+        assert_eq!(m.cheapest(PUZZLE_COSTS), Some(2));
     }
 
     #[test]
@@ -163,6 +143,6 @@ mod tests {
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(875_318_608_908));
     }
 }
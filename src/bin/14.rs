@@ -1,6 +1,8 @@
-use std::io;
-
-use aoc_utils::{grid::Grid, parse};
+use advent_of_code::{
+    grid::Grid,
+    parse,
+    visualize::{self, Colour, Frame},
+};
 use lina::{point2, vec2, Point2, Vec2};
 
 advent_of_code::solution!(14);
@@ -89,27 +91,19 @@ pub fn part_one(input: &str) -> Option<u64> {
         counts[q] += 1;
     }
 
-    let mut view = Grid::new_with_dimensions_uniform(dim, 0);
-    for r in after {
-        view[r.p] += 1;
-    }
-    // println!(
-    //     "{}",
-    //     view.map(|x| char::from_digit(*x as u32, 16).unwrap() as u8)
-    //         .display()
-    // );
-    Some(counts.into_iter().product::<I>() as u64)
-}
-
-fn wait() -> bool {
-    let mut b = String::new();
-    match io::stdin().read_line(&mut b) {
-        Ok(n) => n != 0,
-        Err(error) => {
-            println!("error: {error}");
-            false
+    visualize::frame(|| {
+        let mut view = Grid::new_with_dimensions_uniform(dim, 0);
+        for r in &after {
+            view[r.p] += 1;
         }
-    }
+        Frame::new(&view, |&x| match x {
+            0 => '.',
+            x => char::from_digit(x.min(15) as u32, 16).unwrap(),
+        })
+        .paint('.', Colour::Grey)
+        .caption(format!("after {STEPS} seconds, quadrants {counts:?}"))
+    });
+    Some(counts.into_iter().product::<I>() as u64)
 }
 
 fn filter_possible_tree(robots: &Vec<Robot>) -> bool {
@@ -132,41 +126,34 @@ fn step(mut robots: Vec<Robot>, dim: Vec2<I>) -> Vec<Robot> {
     robots
 }
 
-const INPUT: bool = false;
-
 pub fn part_two(input: &str) -> Option<u64> {
     let (mut r, dim) = parse(input);
 
-    let mut view = Grid::new_with_dimensions_uniform(dim, 0);
     for seconds in 1.. {
         r = step(r, dim);
-        if filter_possible_tree(&r) {
-            if !INPUT {
-                return Some(seconds);
-            }
-            print_grid(&r, &mut view);
-            if !wait() {
-                return Some(seconds);
-            }
+        let tree = filter_possible_tree(&r);
+        visualize::frame(|| robots_frame(&r, dim, seconds, tree));
+        if tree {
+            return Some(seconds);
         }
     }
 
-    println!(
-        "{}",
-        view.map(|x| char::from_digit(*x as u32, 16).unwrap() as u8)
-            .display()
-    );
     None
 }
 
-fn print_grid(r: &Vec<Robot>, view: &mut Grid<i32>) {
-    view.0
-        .iter_mut()
-        .for_each(|x| x.iter_mut().for_each(|y| *y = 0));
-    for robot in r.iter() {
-        view[robot.p] = 1;
+fn robots_frame(r: &[Robot], dim: Vec2<I>, seconds: u64, tree: bool) -> Frame {
+    let mut view = Grid::new_with_dimensions_uniform(dim, ' ');
+    for robot in r {
+        view[robot.p] = '#';
     }
-    println!("{}", view.map(|&x| if x > 0 { '#' } else { ' ' }).display());
+    let caption = if tree {
+        format!("{seconds} s, possible tree")
+    } else {
+        format!("{seconds} s")
+    };
+    Frame::chars(&view)
+        .paint('#', if tree { Colour::Green } else { Colour::Grey })
+        .caption(caption)
 }
 
 #[cfg(test)]
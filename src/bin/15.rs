@@ -1,6 +1,10 @@
 use std::{io::Empty, mem::swap};
 
-use aoc_utils::grid::{Grid, Point, UP_RIGHT_DOWN_LEFT};
+use advent_of_code::{
+    grid::{Grid, Point, UP_RIGHT_DOWN_LEFT},
+    parse::{self, Span},
+    visualize::{self, Colour, Frame},
+};
 use lina::Vec2;
 
 advent_of_code::solution!(15);
@@ -46,17 +50,14 @@ impl Tile2 {
             _ => None,
         }
     }
-}
 
-impl std::fmt::Display for Tile2 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s = match self {
-            Tile2::Wall => "#",
-            Tile2::BoxLeft => "[",
-            Tile2::BoxRight => "]",
-            Tile2::Empty => ".",
-        };
-        write!(f, "{s}")
+    fn char(&self) -> char {
+        match self {
+            Tile2::Wall => '#',
+            Tile2::BoxLeft => '[',
+            Tile2::BoxRight => ']',
+            Tile2::Empty => '.',
+        }
     }
 }
 
@@ -108,24 +109,31 @@ impl std::convert::Into<char> for Tile {
     }
 }
 
-fn parse(input: &str) -> (Grid<Tile>, Robot, Vec<Instruction>) {
-    let mut input = input.split("\n\n");
-    let grid = Grid::read(input.next().expect("Expected a grid"), |x| x as u8);
-    let robot = grid
+/// The warehouse as raw bytes, the robot's position and the moves.
+fn parse_sections(input: &str) -> Result<(Grid<u8>, Robot, Vec<Instruction>), parse::Error> {
+    let [grid, moves] = Span::new(input).sections_exact()?;
+    let robot_grid = grid.grid(|c| u8::try_from(c).ok().filter(|b| b"#.O@".contains(b)))?;
+    let robot = robot_grid
         .position(|x| *x == b'@')
-        .expect("Expected a @ for the robot");
+        .ok_or_else(|| grid.error("expected a `@` for the robot"))?;
+    let instructions = moves.cells(|c| {
+        u8::try_from(c)
+            .ok()
+            .and_then(|b| Instruction::try_from(b).ok())
+    })?;
+
+    Ok((robot_grid, robot, instructions))
+}
+
+fn parse(input: &str) -> Option<(Grid<Tile>, Robot, Vec<Instruction>)> {
+    let (grid, robot, instructions) = parse::report(parse_sections(input))?;
     let grid = grid.map(|&x| Tile::try_from(x).unwrap());
-    let instructions = parse_instructions(input);
 
-    (grid, robot, instructions)
+    Some((grid, robot, instructions))
 }
 
-fn parse2(input: &str) -> (Grid<Tile2>, Robot, Vec<Instruction>) {
-    let mut input = input.split("\n\n");
-    let grid = Grid::read(input.next().expect("Expected a grid"), |x| x as u8);
-    let mut robot = grid
-        .position(|x| *x == b'@')
-        .expect("Expected a @ for the robot");
+fn parse2(input: &str) -> Option<(Grid<Tile2>, Robot, Vec<Instruction>)> {
+    let (grid, mut robot, instructions) = parse::report(parse_sections(input))?;
     robot.x *= 2;
 
     let grid = Grid::new(
@@ -139,32 +147,20 @@ fn parse2(input: &str) -> (Grid<Tile2>, Robot, Vec<Instruction>) {
             })
             .collect(),
     );
-    let instructions = parse_instructions(input);
 
-    (grid, robot, instructions)
+    Some((grid, robot, instructions))
 }
 
-fn parse_instructions(mut input: std::str::Split<'_, &str>) -> Vec<Instruction> {
-    let instructions = input
-        .next()
-        .expect("Expected instructions")
-        .as_bytes()
-        .iter()
-        .filter_map(|&x| Instruction::try_from(x).ok())
-        .collect();
-    instructions
-}
-
-fn print_state(grid: &Grid<Tile>, robot: Robot) {
-    let mut char_grid = grid.char();
-    char_grid[robot] = '@';
-    char_grid.print();
-}
-
-fn print_state2(grid: &Grid<Tile2>, robot: Robot) {
-    let mut char_grid = grid.map(|x| format!("{x}"));
-    char_grid[robot] = "@".to_owned();
-    char_grid.print();
+/// The warehouse after `step` moves, drawn with one character per cell.
+fn warehouse_frame(mut view: Grid<char>, robot: Robot, step: usize) -> Frame {
+    view[robot] = '@';
+    Frame::chars(&view)
+        .paint('#', Colour::Grey)
+        .paint('O', Colour::Yellow)
+        .paint('[', Colour::Yellow)
+        .paint(']', Colour::Yellow)
+        .highlight(robot)
+        .caption(format!("{step} moves"))
 }
 
 fn execute(
@@ -259,10 +255,10 @@ fn execute2(grid: Grid<Tile2>, mut robot: Robot, instruction: Instruction) -> (G
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
-    let (mut grid, mut robot, instructions) = parse(input);
-    for i in instructions {
+    let (mut grid, mut robot, instructions) = parse(input)?;
+    for (step, i) in instructions.into_iter().enumerate() {
         (grid, robot) = execute(grid, robot, i);
-        // print_state(&grid, robot);
+        visualize::frame(|| warehouse_frame(grid.char(), robot, step + 1));
     }
     let scores = Grid::new_with_dimensions(grid.dimension(), |p| {
         if let Tile::Box = grid[p] {
@@ -275,12 +271,10 @@ pub fn part_one(input: &str) -> Option<u64> {
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let (mut grid, mut robot, instructions) = parse2(input);
-    // print_state2(&grid, robot);
-    for i in instructions {
-        // println!("{:?}", i);
+    let (mut grid, mut robot, instructions) = parse2(input)?;
+    for (step, i) in instructions.into_iter().enumerate() {
         (grid, robot) = execute2(grid, robot, i);
-        // print_state2(&grid, robot);
+        visualize::frame(|| warehouse_frame(grid.map(Tile2::char), robot, step + 1));
     }
     let scores = Grid::new_with_dimensions(grid.dimension(), |p| {
         if let Tile2::BoxLeft = grid[p] {
@@ -308,4 +302,13 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(9021));
     }
+
+    #[test]
+    fn test_rejects_non_ascii() {
+        // both would be read as valid bytes if truncated to `#` and `<`.
+        let error = parse_sections("#ģ#\n#@#\n\n<\n").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 2));
+        let error = parse_sections("###\n#@#\n\n<ļ\n").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 2));
+    }
 }
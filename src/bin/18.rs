@@ -1,8 +1,7 @@
-use std::collections::{BinaryHeap, HashSet, VecDeque};
-
-use aoc_utils::{
-    grid::{Grid, Point, UP_RIGHT_DOWN_LEFT},
+use advent_of_code::{
+    grid::{Grid, Point},
     parse,
+    search::{self, Control},
 };
 use itertools::Itertools;
 use lina::{point2, vec2, Point2};
@@ -37,96 +36,49 @@ fn parse(input: &str) -> (Grid<Option<I>>, Vec<Point>) {
 // The time offset means that find the shortest path from END assuming that END
 // is reached time_offset ns before the last obstacle falls
 fn dijkstra(grid: &Grid<Option<I>>, is_empty: impl Fn(Option<I>, I) -> bool) -> Option<I> {
-    let mut queue = BinaryHeap::new();
-    let mut dist = Grid::new_with_dimensions_uniform(grid.dimension(), I::MAX);
-    let mut prev = Grid::new_with_dimensions_uniform(grid.dimension(), None);
-    let end = dist.dimension().to_point() - vec2(1, 1);
-    queue.push(Visit::new(end, 0));
-
-    while let Some(Visit { p, score: k }) = queue.pop() {
-        if k <= dist[p] {
-            // k is the distance from the END to the current.
-            // This means that there is k time before the end.
-            // If the neighbour's time is 5, then 5 ns before
-            // the end, a block will fall, therefore, if we are
-            // more than 5 steps away from the end, the position
-            // will be open.
-            dist[p] = k;
-            for (neighbour, &block_time) in grid.neighbours(p) {
-                if !is_empty(block_time, k) {
-                    continue;
-                }
-                if k + 1 >= dist[neighbour] {
-                    continue;
-                }
-                let n_dist = k + 1;
-                queue.push(Visit::new(neighbour, n_dist));
-                dist[neighbour] = n_dist;
-                prev[neighbour] = Some(p);
-            }
-        }
-    }
-
-    // let path: HashSet<Point> = {
-    //     let mut start = point2(0, 0);
-    //     core::iter::once(start)
-    //         .chain(core::iter::from_fn(|| {
-    //             start = prev[start]?;
-    //             Some(start)
-    //         }))
-    //         .collect()
-    // };
-
-    // Grid::new_with_dimensions(dist.dimension(), |coord| {
-    //     let mut d = match dist[coord] {
-    //         I::MAX => "   #".to_owned(),
-    //         x => format!("{x:>4}"),
-    //     };
-    //     if !is_empty(grid[coord], 0) {
-    //         d = format!("\u{001b}[7m   #\u{001b}[m");
-    //     }
-    //     if path.contains(&coord) {
-    //         // d = format!("\u{001b}[22m{d}\u{001b}[m");
-    //         d = format!("\u{001b}[31m{d}\u{001b}[m");
-    //     }
-    //     d
-    // })
-    // .print();
-    // println!();
-    let res = dist[point2(0, 0)];
-    if res != I::MAX {
-        Some(res)
-    } else {
-        None
-    }
+    let end = grid.dimension().to_point() - vec2(1, 1);
+    // k is the distance from the END to the current.
+    // This means that there is k time before the end.
+    // If the neighbour's time is 5, then 5 ns before
+    // the end, a block will fall, therefore, if we are
+    // more than 5 steps away from the end, the position
+    // will be open.
+    let dist = search::dijkstra_with(
+        [end],
+        |p, k| {
+            grid.neighbours(p)
+                .filter(|&(_, &block_time)| is_empty(block_time, k))
+                .map(|(neighbour, _)| (neighbour, 1))
+                .collect::<Vec<_>>()
+        },
+        |_, _| Control::Continue,
+    );
+
+    dist.cost(point2(0, 0))
 }
 
 fn bfs(grid: &Grid<Option<I>>, is_empty: impl Fn(Option<I>, I) -> bool) -> Option<I> {
-    let mut queue = VecDeque::new();
-    let mut dist = Grid::new_with_dimensions_uniform(grid.dimension(), None);
-    let mut prev = Grid::new_with_dimensions_uniform(grid.dimension(), None);
-    let end = dist.dimension().to_point() - vec2(1, 1);
-
-    dist[end] = Some(0);
-
-    queue.push_back(end);
-
-    while let Some(v) = queue.pop_front() {
-        if v == point2(0, 0) {
-            break;
-        }
-        for (w, &time) in grid.neighbours(v) {
-            if !is_empty(time, 0) {
-                continue;
+    let start = point2(0, 0);
+    let end = grid.dimension().to_point() - vec2(1, 1);
+
+    let dist = search::bfs_with(
+        [end],
+        |v| {
+            grid.neighbours(v)
+                .filter(|&(_, &time)| is_empty(time, 0))
+                .map(|(w, _)| w)
+                .collect::<Vec<_>>()
+        },
+        |v, _| {
+            if v == start {
+                Control::Stop
+            } else {
+                Control::Continue
             }
-            if dist[w].is_none() {
-                dist[w] = dist[v].map(|x| x + 1);
-                prev[w] = Some(v);
-                queue.push_back(w);
-            }
-        }
-    }
-    dist[point2(0, 0)]
+        },
+    );
+
+    dist.cost(start).map(|steps| steps as I)
 }
 
 fn fucking_idk_indiana_jones_time_where_falling_cuts_off_current_path_takes_time_to_move(
@@ -217,28 +169,3 @@ mod tests {
         assert_eq!(result, Some("6,1".to_owned()));
     }
 }
-
-#[derive(Eq, PartialEq)]
-struct Visit {
-    p: Point,
-    score: u64,
-}
-
-impl Visit {
-    fn new(p: Point, score: u64) -> Self {
-        Self { p, score }
-    }
-}
-
-impl PartialOrd for Visit {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.score.partial_cmp(&other.score).map(|x| x.reverse())
-    }
-}
-
-impl Ord for Visit {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.score.cmp(&other.score).reverse()
-    }
-    //
-}
@@ -1,7 +1,6 @@
-use aoc_utils::debug::PrintBytes;
-use aoc_utils::ResultExt;
 use std::{array, collections::HashMap, io::BufRead, ops::Index, rc::Rc, sync::Arc};
 
+use advent_of_code::parse::{self, Span};
 use itertools::Itertools;
 
 advent_of_code::solution!(19);
@@ -22,24 +21,24 @@ fn to(ordinal: u8) -> u8 {
     DEFINED_CHARS[ordinal as usize]
 }
 
-fn parse<'a>(input: &'a str) -> (Vec<Pattern<'a>>, Vec<Pattern<'a>>) {
-    let mut parts = input.split("\n\n");
-    let mut patterns = parts
-        .next()
-        .expect("Expected first part of input")
+fn parse<'a>(input: &'a str) -> Result<(Vec<Pattern<'a>>, Vec<Pattern<'a>>), parse::Error> {
+    let [patterns, goals] = Span::new(input).sections_exact()?;
+    let mut patterns = patterns
         .split(", ")
-        .map(|x| x.as_bytes())
-        .collect_vec();
-    let goals = parts
-        .next()
-        .expect("Expected second part of input")
-        .split_whitespace()
-        .map(|x| x.as_bytes())
-        .collect_vec();
+        .map(colours)
+        .collect::<Result<Vec<_>, _>>()?;
+    let goals = goals.words().map(colours).collect::<Result<Vec<_>, _>>()?;
 
     patterns.sort_unstable();
     patterns.reverse();
-    (patterns, goals)
+    Ok((patterns, goals))
+}
+
+/// A towel or design, checking that it only contains defined colours.
+fn colours(span: Span) -> Result<Pattern, parse::Error> {
+    let span = span.trim();
+    span.cells(|c| u8::try_from(c).ok().filter(|b| DEFINED_CHARS.contains(b)))?;
+    Ok(span.text.as_bytes())
 }
 
 fn find_prefixes_binary<'a>(
@@ -47,7 +46,7 @@ fn find_prefixes_binary<'a>(
     patterns: &'a [Pattern<'a>],
 ) -> impl 'a + Iterator<Item = Pattern<'a>> {
     let last = patterns.binary_search_by(|&el| el.cmp(goal).reverse());
-    let mut search = last.into_inner();
+    let mut search = last.unwrap_or_else(|i| i);
     core::iter::from_fn(move || {
         if search >= patterns.len() {
             return None;
@@ -90,7 +89,7 @@ fn p_hash(memo: &mut HashMap<&'_ [Colour], Out2<'_>>) {
         .iter()
         .map(|(&k, v)| {
             // inspect(k, v.as_ref());
-            println!("{}: {}", k.display(), v.unwrap_or(0))
+            println!("{}: {}", String::from_utf8_lossy(k), v.unwrap_or(0))
         })
         .count());
 }
@@ -187,18 +186,18 @@ fn inspect(goal: Pattern, production: Option<&Vec<Pattern>>) {
                     format!(
                         "\u{001b}[{}m{}",
                         if i % 2 == 0 { "31" } else { "33" },
-                        x.display()
+                        String::from_utf8_lossy(x)
                     )
                 })
                 .join("")
         ),
         None => "\timpossible".to_string(),
     };
-    println!("{}\n{process}\n", goal.display());
+    println!("{}\n{process}\n", String::from_utf8_lossy(goal));
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
-    let (patterns, goals) = parse(input);
+    let (patterns, goals) = parse::report(parse(input))?;
     let mut memo = HashMap::new();
     Some(
         goals
@@ -214,7 +213,7 @@ pub fn part_one(input: &str) -> Option<u64> {
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let (patterns, goals) = parse(input);
+    let (patterns, goals) = parse::report(parse(input))?;
     let mut memo = HashMap::new();
     Some(
         goals
@@ -251,7 +250,7 @@ bbrgwb
     // }
     #[test]
     fn test_prefix() {
-        let (patterns, goals) = parse(&INPUT);
+        let (patterns, goals) = parse(&INPUT).unwrap();
         assert_eq!(goals[0], b"brwrr");
         let prefix_patterns = find_prefixes_collect(goals[0], &patterns);
         assert_eq!(prefix_patterns, &patterns[0..2]);
@@ -261,7 +260,7 @@ bbrgwb
 
     #[test]
     fn test_producable() {
-        let (patterns, goals) = parse(&INPUT);
+        let (patterns, goals) = parse(&INPUT).unwrap();
         let mut hs = HashMap::new();
         assert_eq!(goals[0], b"brwrr");
         assert!(is_producable(b"brwrr", &patterns, &mut hs).is_some());
@@ -290,4 +289,11 @@ bbrgwb
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(16));
     }
+
+    #[test]
+    fn test_rejects_non_ascii() {
+        // `Ţ` would be read as `b` if truncated to a byte.
+        let error = parse("r, bŢ\n\nrb\n").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 5));
+    }
 }
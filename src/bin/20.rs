@@ -1,11 +1,7 @@
-use std::{
-    collections::{HashMap, VecDeque},
-    convert::identity,
-    fmt::Display,
-    ops::Deref,
-};
-
-use aoc_utils::grid::{Grid, Point, UP_RIGHT_DOWN_LEFT};
+use std::{collections::HashMap, convert::identity, fmt::Display, ops::Deref};
+
+use advent_of_code::grid::{Grid, Point, UP_RIGHT_DOWN_LEFT};
+use advent_of_code::search::{self, Control, Search};
 use itertools::Itertools;
 
 advent_of_code::solution!(20);
@@ -27,27 +23,32 @@ fn bfs(
     grid: &Grid<Tile>,
     src: Point,
     max_depth: I,
-    mut f: impl FnMut(Point, I) -> (),
+    mut f: impl FnMut(Point, I),
     check_walls: bool,
-) -> Grid<Option<I>> {
-    let mut queue = VecDeque::new();
-    let mut dist = Grid::new_with_dimensions_uniform(grid.dimension(), None);
-    dist[src] = Some(0);
-    queue.push_back((src, 0));
-
-    while let Some((v, d_v)) = queue.pop_front() {
-        f(v, d_v);
-        for (w, &wall) in grid.neighbours(v) {
-            if check_walls && wall {
-                continue;
-            }
-            if dist[w].is_none() && d_v + 1 <= max_depth {
-                dist[w] = Some(d_v + 1);
-                queue.push_back((w, d_v + 1));
+) -> Search<Point, usize> {
+    search::bfs_with(
+        [src],
+        |v| {
+            grid.neighbours(v)
+                .filter(|&(_, &wall)| !(check_walls && wall))
+                .map(|(w, _)| w)
+                .collect::<Vec<_>>()
+        },
+        |v, d_v| {
+            f(v, d_v as I);
+            if d_v as I >= max_depth {
+                Control::Skip
+            } else {
+                Control::Continue
             }
-        }
-    }
-    dist
+        },
+    )
+}
+
+/// The distance of every track tile to `end`.
+fn distances(grid: &Grid<Tile>, end: Point) -> Grid<Option<I>> {
+    let search = bfs(grid, end, I::MAX, |_, _| {}, true);
+    Grid::new_with_dimensions(grid.dimension(), |p| search.cost(p).map(|d| d as I))
 }
 
 const CHEAT_THRESHOLD: i64 = 100;
@@ -56,7 +57,7 @@ const PICO_DIST: I = 20;
 pub fn part_one(input: &str) -> Option<u64> {
     let (grid, start, end) = parse(input);
 
-    let dists = bfs(&grid, end, I::MAX, |_, _| {}, true);
+    let dists = distances(&grid, end);
     Some(
         cheat_gains(&dists)
             .filter(|&x| x >= CHEAT_THRESHOLD)
@@ -67,14 +68,14 @@ pub fn part_one(input: &str) -> Option<u64> {
 fn group(input: &str) -> HashMap<i64, usize> {
     let (grid, start, end) = parse(input);
 
-    let dists = bfs(&grid, end, I::MAX, |_, _| {}, true);
+    let dists = distances(&grid, end);
     cheat_gains(&dists).counts()
 }
 
 fn group2(input: &str, cheat_time: I) -> HashMap<i64, usize> {
     let (grid, start, end) = parse(input);
 
-    let dists = bfs(&grid, end, I::MAX, |_, _| {}, true);
+    let dists = distances(&grid, end);
     dists
         .iter_coordinates()
         .map(|src| secondary_search(&grid, &dists, src, cheat_time))
@@ -138,7 +139,7 @@ fn secondary_search(
 pub fn part_two(input: &str) -> Option<u64> {
     let (grid, start, end) = parse(input);
 
-    let dists = bfs(&grid, end, I::MAX, |_, _| {}, true);
+    let dists = distances(&grid, end);
 
     Some(
         dists
@@ -202,7 +203,7 @@ mod tests {
 
         let (grid, start, end) = parse(input);
 
-        let dists = bfs(&grid, end, I::MAX, |_, _| {}, true);
+        let dists = distances(&grid, end);
 
         let k: HashMap<_, _> = dists
             .iter_coordinates()
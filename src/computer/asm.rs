@@ -0,0 +1,155 @@
+/// Assembly for the 3-bit computer: one instruction per line like `bst A` or `bxl 5`, optionally prefixed
+/// by its address (`4:`) and followed by a `;` comment, so the output of [`disassemble`] assembles again.
+use super::{Instruction, Opcode, Operand, Program};
+use crate::parse::{self, Span};
+
+/// Assemble `source` into a program.
+pub fn assemble(source: &str) -> Result<Program, parse::Error> {
+    let mut words = vec![];
+
+    for line in Span::new(source).lines() {
+        let code = match line.split_once(";") {
+            Ok((code, _comment)) => code,
+            Err(_) => line,
+        }
+        .trim();
+        if code.is_empty() {
+            continue;
+        }
+
+        let code = match code.split_once(":") {
+            Ok((label, code)) => {
+                let address: usize = label.parse()?;
+                if address != words.len() {
+                    return Err(label.error(format!(
+                        "instruction is at address {}, not {address}",
+                        words.len()
+                    )));
+                }
+                code.trim()
+            }
+            Err(_) => code,
+        };
+
+        let instruction = instruction(code)?;
+        words.extend([instruction.opcode.word(), instruction.operand]);
+    }
+
+    Ok(Program::new(words).expect("assembled words are 3-bit pairs"))
+}
+
+fn instruction(code: Span) -> Result<Instruction, parse::Error> {
+    let mut parts = code.words();
+    let Some(mnemonic) = parts.next() else {
+        return Err(code.error("expected an instruction"));
+    };
+    let opcode = Opcode::from_mnemonic(mnemonic.text)
+        .ok_or_else(|| mnemonic.error(format!("unknown mnemonic `{}`", mnemonic.text)))?;
+
+    let operand = match (parts.next(), opcode.operand()) {
+        (None, Operand::Ignored) => 0,
+        (None, _) => return Err(code.error(format!("`{}` needs an operand", mnemonic.text))),
+        (Some(operand), kind) => self::operand(operand, kind)?,
+    };
+
+    if let Some(extra) = parts.next() {
+        return Err(extra.error(format!("unexpected `{}`", extra.text)));
+    }
+
+    Ok(Instruction { opcode, operand })
+}
+
+fn operand(operand: Span, kind: Operand) -> Result<u8, parse::Error> {
+    let register = ["A", "B", "C"]
+        .iter()
+        .position(|r| r.eq_ignore_ascii_case(operand.text));
+
+    match (register, kind) {
+        #[allow(clippy::cast_possible_truncation)]
+        (Some(register), Operand::Combo) => Ok(4 + register as u8),
+        (Some(_), _) => Err(operand.error(format!(
+            "register `{}` can only be a combo operand",
+            operand.text
+        ))),
+        (None, _) => match operand.parse::<u8>()? {
+            7 if kind == Operand::Combo => Err(operand.error("combo operand 7 is reserved")),
+            value @ 0..=7 => Ok(value),
+            value => Err(operand.error(format!("operand {value} is not a 3-bit number"))),
+        },
+    }
+}
+
+/// One line per instruction with its address and what it does, e.g. ` 2: bxl 5  ; B = B ^ 5`.
+#[must_use]
+pub fn disassemble(program: &Program) -> String {
+    let width = program.words().len().saturating_sub(1).to_string().len();
+    program
+        .instructions()
+        .map(|(address, instruction)| {
+            format!(
+                "{address:>width$}: {:<6} ; {}",
+                instruction.to_string(),
+                instruction.describe()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{assemble, disassemble};
+    use crate::computer::Program;
+
+    #[test]
+    fn assembles_mnemonics() {
+        let program = assemble("adv 1 ; A = A >> 1\nout A\n\njnz 0\n").unwrap();
+        assert_eq!(program.words(), &[0, 1, 5, 4, 3, 0]);
+        assert_eq!(
+            assemble("bxc\nbxl 7\ncdv b").unwrap().words(),
+            &[4, 0, 1, 7, 7, 5]
+        );
+    }
+
+    #[test]
+    fn disassembles_programs() {
+        let program = Program::new(vec![2, 4, 1, 1, 7, 5, 4, 0, 0, 3, 1, 6, 5, 5, 3, 0]).unwrap();
+        let listing = disassemble(&program);
+        assert_eq!(listing.lines().next(), Some(" 0: bst A  ; B = A % 8"));
+        assert_eq!(listing.lines().nth(3), Some(" 6: bxc    ; B = B ^ C"));
+        assert_eq!(
+            listing.lines().last(),
+            Some("14: jnz 0  ; if A != 0 jump to 0")
+        );
+        assert_eq!(assemble(&listing), Ok(program));
+    }
+
+    #[test]
+    fn reports_errors() {
+        let error = |source| {
+            let e = assemble(source).unwrap_err();
+            (e.line, e.column, e.message)
+        };
+        assert_eq!(
+            error("adv 1\nfoo 2"),
+            (2, 1, "unknown mnemonic `foo`".into())
+        );
+        assert_eq!(
+            error("bxl A"),
+            (1, 5, "register `A` can only be a combo operand".into())
+        );
+        assert_eq!(error("out 7"), (1, 5, "combo operand 7 is reserved".into()));
+        assert_eq!(error("adv"), (1, 1, "`adv` needs an operand".into()));
+        assert_eq!(
+            error("2: adv 1"),
+            (1, 1, "instruction is at address 0, not 2".into())
+        );
+        assert_eq!(error("0:\n"), (1, 3, "expected an instruction".into()));
+        assert_eq!(
+            error("adv 1\n2: ; note"),
+            (2, 3, "expected an instruction".into())
+        );
+    }
+}
@@ -0,0 +1,405 @@
+/// Decompile one pass through a program into expressions of the registers at the start of the pass,
+/// e.g. `out (A ^ A >> (A % 8 ^ 1) ^ 7) % 8` and `A = A >> 3`.
+use std::fmt::{Display, Write};
+use std::ops::{BitXor, Shr};
+
+use itertools::Itertools;
+
+use super::{shr, Error, Opcode, Operand, Program, Registers, Value};
+
+/// A value computed by a program. Built with `^`, `>>` and [`Expr::mod8`],
+/// which simplify as they go: constants are folded, xor terms are flattened and cancel out in pairs.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Expr {
+    /// A register at the start of the pass.
+    A,
+    B,
+    C,
+    Lit(Value),
+    /// At least two terms, none of them a `Xor`, with at most one `Lit` at the end.
+    Xor(Vec<Expr>),
+    Mod8(Box<Expr>),
+    Shr(Box<Expr>, Box<Expr>),
+}
+
+/// Binding strength of `^`, `>>` and `%`, the same in Rust and Python.
+const XOR: u8 = 1;
+const SHR: u8 = 2;
+const MOD: u8 = 3;
+const ATOM: u8 = 4;
+
+impl Expr {
+    #[must_use]
+    pub fn mod8(self) -> Expr {
+        match self {
+            Expr::Lit(value) => Expr::Lit(value % 8),
+            expr @ Expr::Mod8(_) => expr,
+            // (x % 8 ^ y) % 8 == (x ^ y) % 8
+            Expr::Xor(terms) => {
+                let inner = terms
+                    .into_iter()
+                    .map(|term| match term {
+                        Expr::Mod8(inner) => *inner,
+                        Expr::Lit(value) => Expr::Lit(value % 8),
+                        term => term,
+                    })
+                    .fold(Expr::Lit(0), |acc, term| acc ^ term);
+                match inner {
+                    Expr::Lit(value) => Expr::Lit(value % 8),
+                    inner => Expr::Mod8(Box::new(inner)),
+                }
+            }
+            expr => Expr::Mod8(Box::new(expr)),
+        }
+    }
+
+    #[must_use]
+    pub fn eval(&self, registers: &Registers) -> Value {
+        match self {
+            Expr::A => registers.a,
+            Expr::B => registers.b,
+            Expr::C => registers.c,
+            Expr::Lit(value) => *value,
+            Expr::Xor(terms) => terms.iter().fold(0, |acc, term| acc ^ term.eval(registers)),
+            Expr::Mod8(inner) => inner.eval(registers) % 8,
+            Expr::Shr(inner, amount) => shr(inner.eval(registers), amount.eval(registers)),
+        }
+    }
+
+    /// A closure that evaluates the expression without matching on it every time.
+    #[must_use]
+    pub fn compile(&self) -> Box<dyn Fn(&Registers) -> Value> {
+        match self {
+            Expr::A => Box::new(|r| r.a),
+            Expr::B => Box::new(|r| r.b),
+            Expr::C => Box::new(|r| r.c),
+            &Expr::Lit(value) => Box::new(move |_| value),
+            Expr::Xor(terms) => {
+                let terms: Vec<_> = terms.iter().map(Expr::compile).collect();
+                Box::new(move |r| terms.iter().fold(0, |acc, term| acc ^ term(r)))
+            }
+            Expr::Mod8(inner) => {
+                let inner = inner.compile();
+                Box::new(move |r| inner(r) % 8)
+            }
+            Expr::Shr(inner, amount) => {
+                let inner = inner.compile();
+                match **amount {
+                    // simplified literal shifts are below 64.
+                    Expr::Lit(amount) => Box::new(move |r| inner(r) >> amount),
+                    ref amount => {
+                        let amount = amount.compile();
+                        Box::new(move |r| shr(inner(r), amount(r)))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether the expression reads `register`.
+    #[must_use]
+    pub fn uses(&self, register: &Expr) -> bool {
+        match self {
+            Expr::Xor(terms) => terms.iter().any(|term| term.uses(register)),
+            Expr::Mod8(inner) => inner.uses(register),
+            Expr::Shr(inner, amount) => inner.uses(register) || amount.uses(register),
+            expr => expr == register,
+        }
+    }
+
+    /// Source of an equivalent Rust closure taking the registers it uses, e.g. `|a: u64| a % 8 ^ 5`.
+    #[must_use]
+    pub fn to_rust(&self) -> String {
+        let params = [(Expr::A, "a"), (Expr::B, "b"), (Expr::C, "c")]
+            .iter()
+            .filter(|(register, _)| self.uses(register))
+            .map(|(_, name)| format!("{name}: u64"))
+            .join(", ");
+        let mut body = String::new();
+        self.write(&mut body, XOR, true);
+
+        if body.contains("shr(") {
+            format!(
+                "|{params}| {{\n    let shr = |x: u64, n: u64| u32::try_from(n).ok().and_then(|n| x.checked_shr(n)).unwrap_or(0);\n    {body}\n}}"
+            )
+        } else {
+            format!("|{params}| {body}")
+        }
+    }
+
+    /// Shifts by an expression use a `shr` function in Rust, a plain `>>` could overflow.
+    fn precedence(&self, rust: bool) -> u8 {
+        match self {
+            Expr::Xor(_) => XOR,
+            Expr::Shr(_, amount) if !rust || matches!(**amount, Expr::Lit(_)) => SHR,
+            Expr::Mod8(_) => MOD,
+            _ => ATOM,
+        }
+    }
+
+    /// Write the expression, in parentheses if it binds weaker than `min`.
+    /// Rust also gets them around `%` mixed with bit operators, as clippy asks for.
+    fn write(&self, out: &mut String, min: u8, rust: bool) {
+        let parens =
+            self.precedence(rust) < min || (rust && matches!(self, Expr::Mod8(_)) && min > XOR);
+        if parens {
+            out.push('(');
+        }
+
+        match self {
+            Expr::A => out.push(if rust { 'a' } else { 'A' }),
+            Expr::B => out.push(if rust { 'b' } else { 'B' }),
+            Expr::C => out.push(if rust { 'c' } else { 'C' }),
+            Expr::Lit(value) => write!(out, "{value}").unwrap(),
+            Expr::Xor(terms) => {
+                for (i, term) in terms.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(" ^ ");
+                    }
+                    term.write(out, SHR, rust);
+                }
+            }
+            Expr::Mod8(inner) => {
+                inner.write(out, MOD, rust);
+                out.push_str(" % 8");
+            }
+            Expr::Shr(inner, amount) if self.precedence(rust) == ATOM => {
+                out.push_str("shr(");
+                inner.write(out, XOR, rust);
+                out.push_str(", ");
+                amount.write(out, XOR, rust);
+                out.push(')');
+            }
+            Expr::Shr(inner, amount) => {
+                inner.write(out, SHR, rust);
+                out.push_str(" >> ");
+                amount.write(out, MOD, rust);
+            }
+        }
+
+        if parens {
+            out.push(')');
+        }
+    }
+}
+
+impl BitXor for Expr {
+    type Output = Expr;
+
+    fn bitxor(self, other: Expr) -> Expr {
+        let mut constant = 0;
+        let mut terms: Vec<Expr> = vec![];
+        for term in [self, other].into_iter().flat_map(|term| match term {
+            Expr::Xor(terms) => terms,
+            term => vec![term],
+        }) {
+            match term {
+                Expr::Lit(value) => constant ^= value,
+                // x ^ x == 0
+                term => match terms.iter().position(|t| *t == term) {
+                    Some(i) => {
+                        terms.remove(i);
+                    }
+                    None => terms.push(term),
+                },
+            }
+        }
+
+        if constant != 0 || terms.is_empty() {
+            terms.push(Expr::Lit(constant));
+        }
+        match terms.len() {
+            1 => terms.pop().unwrap(),
+            _ => Expr::Xor(terms),
+        }
+    }
+}
+
+impl Shr for Expr {
+    type Output = Expr;
+
+    fn shr(self, amount: Expr) -> Expr {
+        match (self, amount) {
+            (expr, Expr::Lit(0)) => expr,
+            (Expr::Lit(0), _) | (_, Expr::Lit(64..)) => Expr::Lit(0),
+            (Expr::Lit(value), Expr::Lit(amount)) => Expr::Lit(shr(value, amount)),
+            // x >> m >> n == x >> (m + n)
+            (Expr::Shr(inner, first), Expr::Lit(amount)) => match *first {
+                Expr::Lit(first) => *inner >> Expr::Lit(first.saturating_add(amount)),
+                first => Expr::Shr(
+                    Box::new(Expr::Shr(inner, Box::new(first))),
+                    Box::new(Expr::Lit(amount)),
+                ),
+            },
+            (expr, amount) => Expr::Shr(Box::new(expr), Box::new(amount)),
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut out = String::new();
+        self.write(&mut out, XOR, false);
+        f.write_str(&out)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The effect of running a program once from the start up to its final `jnz 0`, or to its end.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pass {
+    pub outputs: Vec<Expr>,
+    /// `A`, `B` and `C` at the end of the pass.
+    pub registers: [Expr; 3],
+    /// Whether the program ends with `jnz 0`, repeating the pass until `A` is zero.
+    pub repeats: bool,
+}
+
+impl Pass {
+    /// Execute the program symbolically. Fails on jumps other than a final `jnz 0`.
+    pub fn decompile(program: &Program) -> Result<Pass, Error> {
+        let mut instructions: Vec<_> = program.instructions().collect();
+        let repeats = matches!(
+            instructions.last(),
+            Some((_, instruction)) if instruction.opcode == Opcode::Jnz && instruction.operand == 0
+        );
+        if repeats {
+            instructions.pop();
+        }
+
+        let [mut a, mut b, mut c] = [Expr::A, Expr::B, Expr::C];
+        let mut outputs = vec![];
+        for (address, instruction) in instructions {
+            let value = match (instruction.opcode.operand(), instruction.operand) {
+                (Operand::Combo, 4) => a.clone(),
+                (Operand::Combo, 5) => b.clone(),
+                (Operand::Combo, 6) => c.clone(),
+                (Operand::Combo, 7) => return Err(Error::ReservedCombo { address }),
+                (_, operand) => Expr::Lit(operand.into()),
+            };
+
+            match instruction.opcode {
+                Opcode::Adv => a = a >> value,
+                Opcode::Bxl => b = b ^ value,
+                Opcode::Bst => b = value.mod8(),
+                Opcode::Jnz => return Err(Error::Jump { address }),
+                Opcode::Bxc => b = b ^ c.clone(),
+                Opcode::Out => outputs.push(value.mod8()),
+                Opcode::Bdv => b = a.clone() >> value,
+                Opcode::Cdv => c = a.clone() >> value,
+            }
+        }
+
+        Ok(Pass {
+            outputs,
+            registers: [a, b, c],
+            repeats,
+        })
+    }
+}
+
+impl Display for Pass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut lines: Vec<String> = self
+            .outputs
+            .iter()
+            .map(|out| format!("out {out}"))
+            .collect();
+        for (name, (register, initial)) in
+            ["A", "B", "C"]
+                .iter()
+                .zip(self.registers.iter().zip([Expr::A, Expr::B, Expr::C]))
+        {
+            if *register != initial {
+                lines.push(format!("{name} = {register}"));
+            }
+        }
+        if self.repeats {
+            lines.push("repeat while A != 0".into());
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Expr, Pass};
+    use crate::computer::{assemble, Cpu, Error, Program, Registers};
+
+    const PROGRAM: [u8; 16] = [2, 4, 1, 1, 7, 5, 4, 0, 0, 3, 1, 6, 5, 5, 3, 0];
+
+    #[test]
+    fn simplifies() {
+        let a = || Expr::A;
+        assert_eq!(a() ^ Expr::Lit(1) ^ Expr::Lit(1), Expr::A);
+        assert_eq!(a() ^ Expr::B ^ Expr::A, Expr::B);
+        assert_eq!(
+            (a().mod8() ^ Expr::Lit(13)).mod8().to_string(),
+            "(A ^ 5) % 8"
+        );
+        assert_eq!((a() >> Expr::Lit(3) >> Expr::Lit(2)).to_string(), "A >> 5");
+        assert_eq!(a() >> Expr::Lit(70), Expr::Lit(0));
+        assert_eq!(Expr::Lit(9) >> Expr::Lit(1) ^ Expr::Lit(4), Expr::Lit(0));
+    }
+
+    #[test]
+    fn decompiles_a_pass() {
+        let pass = Pass::decompile(&Program::new(PROGRAM.to_vec()).unwrap()).unwrap();
+        assert_eq!(
+            pass.to_string(),
+            "out (A ^ A >> (A % 8 ^ 1) ^ 7) % 8\nA = A >> 3\nB = A % 8 ^ A >> (A % 8 ^ 1) ^ 7\nC = A >> (A % 8 ^ 1)\nrepeat while A != 0"
+        );
+
+        let example = Pass::decompile(&assemble("adv 3\nout A\njnz 0").unwrap()).unwrap();
+        assert_eq!(example.outputs, vec![(Expr::A >> Expr::Lit(3)).mod8()]);
+
+        assert_eq!(
+            Pass::decompile(&assemble("jnz 2\nout A").unwrap()),
+            Err(Error::Jump { address: 0 })
+        );
+    }
+
+    #[test]
+    fn agrees_with_the_cpu() {
+        let program = Program::new(PROGRAM.to_vec()).unwrap();
+        let pass = Pass::decompile(&program).unwrap();
+        let output = pass.outputs[0].compile();
+        // generated by `pass.outputs[0].to_rust()`
+        let generated = |a: u64| {
+            let shr = |x: u64, n: u64| {
+                u32::try_from(n)
+                    .ok()
+                    .and_then(|n| x.checked_shr(n))
+                    .unwrap_or(0)
+            };
+            (a ^ shr(a, (a % 8) ^ 1) ^ 7) % 8
+        };
+
+        for a in [0, 1, 0o7, 0o1234567, 247_839_653_009_594, u64::MAX] {
+            let registers = Registers {
+                a,
+                ..Registers::default()
+            };
+            let mut cpu = Cpu::new(program.clone(), registers);
+            let out = u64::from(cpu.next_output().unwrap().unwrap());
+            assert_eq!(pass.outputs[0].eval(&registers), out);
+            assert_eq!(output(&registers), out);
+            assert_eq!(generated(a), out);
+            assert_eq!(pass.registers[0].eval(&registers), a >> 3);
+        }
+    }
+
+    #[test]
+    fn generates_rust() {
+        let pass = Pass::decompile(&Program::new(PROGRAM.to_vec()).unwrap()).unwrap();
+        assert_eq!(
+            pass.outputs[0].to_rust(),
+            "|a: u64| {\n    let shr = |x: u64, n: u64| u32::try_from(n).ok().and_then(|n| x.checked_shr(n)).unwrap_or(0);\n    (a ^ shr(a, (a % 8) ^ 1) ^ 7) % 8\n}"
+        );
+        let shifts = (Expr::B ^ Expr::A >> Expr::Lit(3)).mod8();
+        assert_eq!(shifts.to_rust(), "|a: u64, b: u64| (b ^ a >> 3) % 8");
+    }
+}
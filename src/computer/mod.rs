@@ -0,0 +1,464 @@
+/// The 3-bit computer of 2024 day 17: a program of 3-bit words run on the registers `A`, `B` and `C`.
+/// [`assemble`] and [`disassemble`] convert between programs and mnemonics like `adv 3` or `out B`,
+/// [`Cpu::trace`] records the registers after every executed instruction and [`Pass::decompile`] turns
+/// one pass through a program into expressions of the registers.
+use std::fmt::Display;
+
+use itertools::Itertools;
+
+use crate::parse::{self, Span};
+
+mod asm;
+mod decompile;
+mod quine;
+mod trace;
+
+pub use asm::{assemble, disassemble};
+pub use decompile::{Expr, Pass};
+pub use quine::{find_a, find_quine, search_bits, Loop};
+pub use trace::{End, Step, Trace};
+
+pub type Value = u64;
+
+/// `value >> amount`, shifting by the width or more shifts every bit out instead of overflowing.
+#[must_use]
+pub fn shr(value: Value, amount: Value) -> Value {
+    u32::try_from(amount)
+        .ok()
+        .and_then(|amount| value.checked_shr(amount))
+        .unwrap_or(0)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A word of a program is not a 3-bit number.
+    InvalidWord { address: usize, word: u8 },
+    /// The last opcode of a program has no operand.
+    OddLength,
+    /// Combo operand 7 is reserved and cannot be evaluated.
+    ReservedCombo { address: usize },
+    /// A jump inside the loop of a program, which cannot be decompiled.
+    Jump { address: usize },
+    /// The search for `A` gave up with too many candidates after fixing its bits up to `bit`.
+    TooManyCandidates { bit: u32 },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidWord { address, word } => {
+                write!(f, "word {word} at address {address} is not a 3-bit number")
+            }
+            Error::OddLength => write!(f, "the last opcode has no operand"),
+            Error::ReservedCombo { address } => {
+                write!(f, "reserved combo operand 7 at address {address}")
+            }
+            Error::Jump { address } => {
+                write!(f, "jump at address {address} inside the loop")
+            }
+            Error::TooManyCandidates { bit } => {
+                write!(f, "too many candidates for A after fixing {} bits", bit + 1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Print the error of a failed run to stderr, so a solution can bail out with `?`.
+pub fn report<T>(result: Result<T, Error>) -> Option<T> {
+    result
+        .map_err(|e| eprintln!("Failed to run program: {e}"))
+        .ok()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Opcode {
+    Adv,
+    Bxl,
+    Bst,
+    Jnz,
+    Bxc,
+    Out,
+    Bdv,
+    Cdv,
+}
+
+/// How an opcode interprets its operand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operand {
+    /// `0` to `3` stand for themselves, `4` to `6` for the registers `A` to `C`.
+    Combo,
+    Literal,
+    Ignored,
+}
+
+impl Opcode {
+    /// All opcodes, indexed by their 3-bit encoding.
+    pub const ALL: [Opcode; 8] = [
+        Opcode::Adv,
+        Opcode::Bxl,
+        Opcode::Bst,
+        Opcode::Jnz,
+        Opcode::Bxc,
+        Opcode::Out,
+        Opcode::Bdv,
+        Opcode::Cdv,
+    ];
+
+    #[must_use]
+    pub fn from_word(word: u8) -> Option<Self> {
+        Self::ALL.get(usize::from(word)).copied()
+    }
+
+    #[must_use]
+    pub fn word(self) -> u8 {
+        self as u8
+    }
+
+    #[must_use]
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Opcode::Adv => "adv",
+            Opcode::Bxl => "bxl",
+            Opcode::Bst => "bst",
+            Opcode::Jnz => "jnz",
+            Opcode::Bxc => "bxc",
+            Opcode::Out => "out",
+            Opcode::Bdv => "bdv",
+            Opcode::Cdv => "cdv",
+        }
+    }
+
+    #[must_use]
+    pub fn from_mnemonic(mnemonic: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|opcode| opcode.mnemonic().eq_ignore_ascii_case(mnemonic))
+    }
+
+    #[must_use]
+    pub fn operand(self) -> Operand {
+        match self {
+            Opcode::Adv | Opcode::Bst | Opcode::Out | Opcode::Bdv | Opcode::Cdv => Operand::Combo,
+            Opcode::Bxl | Opcode::Jnz => Operand::Literal,
+            Opcode::Bxc => Operand::Ignored,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub operand: u8,
+}
+
+impl Instruction {
+    /// The operand as written in assembly: combo operands `4` to `6` are the registers `A` to `C`.
+    #[must_use]
+    pub fn operand_name(&self) -> Option<String> {
+        match (self.opcode.operand(), self.operand) {
+            (Operand::Ignored, 0) => None,
+            (Operand::Combo, 4..=6) => Some(["A", "B", "C"][usize::from(self.operand - 4)].into()),
+            (Operand::Combo, 7) => Some("?7".into()),
+            (_, operand) => Some(operand.to_string()),
+        }
+    }
+
+    /// What the instruction does, e.g. `C = A >> B`.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        let operand = self.operand_name().unwrap_or_default();
+        match self.opcode {
+            Opcode::Adv => format!("A = A >> {operand}"),
+            Opcode::Bxl => format!("B = B ^ {operand}"),
+            Opcode::Bst => format!("B = {operand} % 8"),
+            Opcode::Jnz => format!("if A != 0 jump to {operand}"),
+            Opcode::Bxc => "B = B ^ C".to_string(),
+            Opcode::Out => format!("out {operand} % 8"),
+            Opcode::Bdv => format!("B = A >> {operand}"),
+            Opcode::Cdv => format!("C = A >> {operand}"),
+        }
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.operand_name() {
+            Some(operand) => write!(f, "{} {operand}", self.opcode.mnemonic()),
+            None => write!(f, "{}", self.opcode.mnemonic()),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A program as the 3-bit words it is stored as. Jumps address words, instructions start at even addresses.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Program(Vec<u8>);
+
+impl Program {
+    pub fn new(words: Vec<u8>) -> Result<Self, Error> {
+        if let Some((address, &word)) = words.iter().find_position(|&&word| word > 7) {
+            return Err(Error::InvalidWord { address, word });
+        }
+        if words.len() % 2 == 1 {
+            return Err(Error::OddLength);
+        }
+        Ok(Program(words))
+    }
+
+    #[must_use]
+    pub fn words(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// The instruction starting at `address`, `None` past the end of the program.
+    #[must_use]
+    pub fn instruction(&self, address: usize) -> Option<Instruction> {
+        let opcode = Opcode::from_word(*self.0.get(address)?)?;
+        let operand = *self.0.get(address + 1)?;
+        Some(Instruction { opcode, operand })
+    }
+
+    /// Every instruction with its address.
+    pub fn instructions(&self) -> impl Iterator<Item = (usize, Instruction)> + '_ {
+        (0..self.0.len())
+            .step_by(2)
+            .filter_map(|address| Some((address, self.instruction(address)?)))
+    }
+}
+
+/// The words separated by commas, as in the puzzle input.
+impl Display for Program {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.iter().join(","))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Registers {
+    pub a: Value,
+    pub b: Value,
+    pub c: Value,
+}
+
+/// Registers are printed in octal, where every output of a typical program is one digit of `A`.
+impl Display for Registers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "A={:o} B={:o} C={:o}", self.a, self.b, self.c)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cpu {
+    pub program: Program,
+    pub registers: Registers,
+    pub pc: usize,
+}
+
+impl Cpu {
+    #[must_use]
+    pub fn new(program: Program, registers: Registers) -> Self {
+        Cpu {
+            program,
+            registers,
+            pc: 0,
+        }
+    }
+
+    /// Read the puzzle input: `Register A: 729` for each register, a blank line and `Program: 0,1,5,4,3,0`.
+    pub fn parse(input: &str) -> Result<Self, parse::Error> {
+        let [registers, program] = Span::new(input).sections_exact()?;
+        let register = |line: Span, name: &str| -> Result<Value, parse::Error> {
+            let (key, value) = line.key_value(":")?;
+            key.expect(name)?;
+            value.parse()
+        };
+
+        let [a, b, c] = registers.lines_exact()?;
+        let registers = Registers {
+            a: register(a, "Register A")?,
+            b: register(b, "Register B")?,
+            c: register(c, "Register C")?,
+        };
+
+        let (key, words) = program.trim().key_value(":")?;
+        key.expect("Program")?;
+        let words = words
+            .split(",")
+            .map(Span::parse)
+            .collect::<Result<Vec<u8>, _>>()?;
+        let program = Program::new(words).map_err(|e| program.error(e.to_string()))?;
+
+        Ok(Cpu::new(program, registers))
+    }
+
+    /// Start over at the first instruction with `registers`.
+    pub fn reset(&mut self, registers: Registers) {
+        self.registers = registers;
+        self.pc = 0;
+    }
+
+    fn operand(&self, instruction: Instruction) -> Result<Value, Error> {
+        let registers = self.registers;
+        match (instruction.opcode.operand(), instruction.operand) {
+            (Operand::Combo, 4) => Ok(registers.a),
+            (Operand::Combo, 5) => Ok(registers.b),
+            (Operand::Combo, 6) => Ok(registers.c),
+            (Operand::Combo, 7) => Err(Error::ReservedCombo { address: self.pc }),
+            (_, operand) => Ok(operand.into()),
+        }
+    }
+
+    /// Execute one instruction. Returns `None` once the program halted.
+    pub fn step(&mut self) -> Result<Option<Step>, Error> {
+        let Some(instruction) = self.program.instruction(self.pc) else {
+            return Ok(None);
+        };
+
+        let value = self.operand(instruction)?;
+
+        let pc = self.pc;
+        let r = &mut self.registers;
+        let mut out = None;
+        self.pc += 2;
+
+        match instruction.opcode {
+            Opcode::Adv => r.a = shr(r.a, value),
+            Opcode::Bxl => r.b ^= value,
+            Opcode::Bst => r.b = value % 8,
+            Opcode::Jnz => {
+                if r.a != 0 {
+                    self.pc = usize::try_from(value).unwrap();
+                }
+            }
+            Opcode::Bxc => r.b ^= r.c,
+            #[allow(clippy::cast_possible_truncation)]
+            Opcode::Out => out = Some((value % 8) as u8),
+            Opcode::Bdv => r.b = shr(r.a, value),
+            Opcode::Cdv => r.c = shr(r.a, value),
+        }
+
+        Ok(Some(Step {
+            pc,
+            instruction,
+            registers: self.registers,
+            out,
+        }))
+    }
+
+    /// Run until the next output, `None` if the program halts first.
+    pub fn next_output(&mut self) -> Result<Option<u8>, Error> {
+        while let Some(step) = self.step()? {
+            if step.out.is_some() {
+                return Ok(step.out);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Run until the program halts and return everything it output.
+    pub fn run(&mut self) -> Result<Vec<u8>, Error> {
+        std::iter::from_fn(|| self.next_output().transpose()).collect()
+    }
+
+    /// Run until the program halts, joining the outputs with commas.
+    pub fn output(&mut self) -> Result<String, Error> {
+        Ok(self.run()?.iter().join(","))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Cpu, Error, Instruction, Opcode, Program, Registers};
+
+    const EXAMPLE: &str = "Register A: 729\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0\n";
+
+    fn run(words: Vec<u8>, registers: Registers) -> (Vec<u8>, Registers) {
+        let mut cpu = Cpu::new(Program::new(words).unwrap(), registers);
+        let out = cpu.run().unwrap();
+        (out, cpu.registers)
+    }
+
+    #[test]
+    fn runs_the_example() {
+        let mut cpu = Cpu::parse(EXAMPLE).unwrap();
+        assert_eq!(cpu.registers.a, 729);
+        assert_eq!(cpu.program.to_string(), "0,1,5,4,3,0");
+        assert_eq!(cpu.output().unwrap(), "4,6,3,5,6,3,5,2,1,0");
+    }
+
+    #[test]
+    fn executes_instructions() {
+        let c = Registers {
+            c: 9,
+            ..Registers::default()
+        };
+        assert_eq!(run(vec![2, 6], c).1.b, 1);
+
+        let a = Registers {
+            a: 10,
+            ..Registers::default()
+        };
+        assert_eq!(run(vec![5, 0, 5, 1, 5, 4], a).0, vec![0, 1, 2]);
+
+        let a = Registers {
+            a: 2024,
+            ..Registers::default()
+        };
+        let (out, registers) = run(vec![0, 1, 5, 4, 3, 0], a);
+        assert_eq!(out, vec![4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]);
+        assert_eq!(registers.a, 0);
+
+        let b = Registers {
+            b: 29,
+            ..Registers::default()
+        };
+        assert_eq!(run(vec![1, 7], b).1.b, 26);
+
+        let bc = Registers {
+            b: 2024,
+            c: 43690,
+            ..Registers::default()
+        };
+        assert_eq!(run(vec![4, 0], bc).1.b, 44354);
+    }
+
+    #[test]
+    fn rejects_invalid_programs() {
+        assert_eq!(
+            Program::new(vec![0, 8]),
+            Err(Error::InvalidWord {
+                address: 1,
+                word: 8
+            })
+        );
+        assert_eq!(Program::new(vec![0, 1, 5]), Err(Error::OddLength));
+
+        let mut cpu = Cpu::new(Program::new(vec![5, 7]).unwrap(), Registers::default());
+        assert_eq!(cpu.run(), Err(Error::ReservedCombo { address: 0 }));
+
+        let error = Cpu::parse("Register A: 1\nRegister B: 0\n\nProgram: 0,1").unwrap_err();
+        assert_eq!(
+            (error.line, error.message.as_str()),
+            (1, "expected 3 lines, found 2")
+        );
+    }
+
+    #[test]
+    fn names_operands() {
+        let instruction = |opcode, operand| Instruction { opcode, operand };
+        assert_eq!(instruction(Opcode::Cdv, 5).to_string(), "cdv B");
+        assert_eq!(instruction(Opcode::Bxl, 5).to_string(), "bxl 5");
+        assert_eq!(instruction(Opcode::Bxc, 0).to_string(), "bxc");
+        assert_eq!(instruction(Opcode::Bst, 4).describe(), "B = A % 8");
+        assert_eq!(Opcode::from_mnemonic("JNZ"), Some(Opcode::Jnz));
+    }
+}
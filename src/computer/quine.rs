@@ -0,0 +1,400 @@
+/// Find the smallest initial `A` that makes a program output a given sequence, e.g. the program itself.
+/// Programs with a single loop that shifts `A` by a constant are solved digit by digit from the last output,
+/// anything else by a search over the bits of `A` that simulates with partially known registers.
+use super::{Cpu, Error, Opcode, Operand, Pass, Program, Registers, Value};
+
+/// Most instructions a candidate may execute before it is rejected as looping forever.
+const MAX_STEPS: usize = 100_000;
+/// Most prefixes of `A` kept per bit by [`search_bits`], so hopeless programs fail instead of eating memory.
+const MAX_FRONTIER: usize = 1 << 20;
+
+/// The smallest initial `A` that makes `cpu` output its own program.
+pub fn find_quine(cpu: &Cpu) -> Result<Option<Value>, Error> {
+    find_a(cpu, cpu.program.words())
+}
+
+/// The smallest initial `A` that makes `cpu` output exactly `target`, `B` and `C` keep their values.
+/// `None` if there is no such `A`, an error if the search gave up.
+pub fn find_a(cpu: &Cpu, target: &[u8]) -> Result<Option<Value>, Error> {
+    match Loop::detect(&cpu.program) {
+        // the digit search is exhaustive, no need to search again if it failed.
+        Some(program_loop) => Ok(program_loop.find_a(cpu, target)),
+        None => search_bits(cpu, target),
+    }
+}
+
+/// Whether `cpu` started with `a` outputs exactly `target` and halts.
+fn outputs(cpu: &Cpu, a: Value, target: &[u8]) -> bool {
+    let mut cpu = cpu.clone();
+    cpu.reset(Registers { a, ..cpu.registers });
+
+    let mut produced = 0;
+    for _ in 0..MAX_STEPS {
+        match cpu.step() {
+            Ok(Some(step)) => {
+                if let Some(out) = step.out {
+                    if target.get(produced) != Some(&out) {
+                        return false;
+                    }
+                    produced += 1;
+                }
+            }
+            Ok(None) => return produced == target.len(),
+            Err(_) => return false,
+        }
+    }
+    false
+}
+
+/* ---------------------------------- Loops --------------------------------- */
+
+/// A program that is one loop: `jnz 0` at the end, a single `out` and a single `adv` by a literal,
+/// with `B` and `C` written before they are read. Every pass outputs one value that only depends on
+/// `A` at the start of the pass, and `A` loses its lowest `shift` bits per pass.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Loop {
+    pub shift: u32,
+}
+
+impl Loop {
+    #[must_use]
+    pub fn detect(program: &Program) -> Option<Loop> {
+        let instructions: Vec<_> = program.instructions().map(|(_, i)| i).collect();
+        let (last, body) = instructions.split_last()?;
+        if last.opcode != Opcode::Jnz || last.operand != 0 {
+            return None;
+        }
+
+        let count = |opcode| body.iter().filter(|i| i.opcode == opcode).count();
+        if count(Opcode::Jnz) != 0 || count(Opcode::Out) != 1 || count(Opcode::Adv) != 1 {
+            return None;
+        }
+
+        let (mut b_written, mut c_written) = (false, false);
+        let mut shift = None;
+        for instruction in body {
+            let combo = instruction.opcode.operand() == Operand::Combo;
+            let reads_b = matches!(instruction.opcode, Opcode::Bxl | Opcode::Bxc)
+                || (combo && instruction.operand == 5);
+            let reads_c = instruction.opcode == Opcode::Bxc || (combo && instruction.operand == 6);
+            if (reads_b && !b_written) || (reads_c && !c_written) {
+                return None;
+            }
+
+            match instruction.opcode {
+                Opcode::Adv => match instruction.operand {
+                    1..=3 => shift = Some(u32::from(instruction.operand)),
+                    _ => return None,
+                },
+                Opcode::Bxl | Opcode::Bst | Opcode::Bxc | Opcode::Bdv => b_written = true,
+                Opcode::Cdv => c_written = true,
+                Opcode::Jnz | Opcode::Out => {}
+            }
+        }
+
+        shift.map(|shift| Loop { shift })
+    }
+
+    /// The smallest `A` for `target`, choosing `shift` bits per output starting from the last one.
+    #[must_use]
+    pub fn find_a(&self, cpu: &Cpu, target: &[u8]) -> Option<Value> {
+        // loops are straight-line code with one output.
+        let output = Pass::decompile(&cpu.program)
+            .ok()?
+            .outputs
+            .first()?
+            .compile();
+        self.digits(cpu, &output, target, target.len(), 0)
+    }
+
+    /// Extend `a`, the value of `A` at the start of the pass that outputs `target[remaining]`, with one digit
+    /// per remaining output. Digits are tried in ascending order, so the first solution is the smallest.
+    fn digits(
+        &self,
+        cpu: &Cpu,
+        output: &dyn Fn(&Registers) -> Value,
+        target: &[u8],
+        remaining: usize,
+        a: Value,
+    ) -> Option<Value> {
+        let Some(rest) = remaining.checked_sub(1) else {
+            return outputs(cpu, a, target).then_some(a);
+        };
+        if a.leading_zeros() < self.shift {
+            return None;
+        }
+
+        (0..1 << self.shift)
+            .map(|digit| a << self.shift | digit)
+            // a pass only runs again if `A` is not zero.
+            .filter(|&x| x != 0 || rest == 0)
+            .filter(|&a| output(&Registers { a, ..cpu.registers }) == Value::from(target[rest]))
+            .find_map(|x| self.digits(cpu, output, target, rest, x))
+    }
+}
+
+/* ---------------------------------- Bits ---------------------------------- */
+
+/// A register of which only some bits are known, unknown bits of `value` are zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Bits {
+    known: u64,
+    value: u64,
+}
+
+impl Bits {
+    fn known(value: Value) -> Self {
+        Bits { known: !0, value }
+    }
+
+    /// The lowest `count` bits of `value`.
+    fn low(value: Value, count: u32) -> Self {
+        let known = u64::MAX.checked_shr(64 - count).unwrap_or(0);
+        Bits {
+            known,
+            value: value & known,
+        }
+    }
+
+    fn get(self) -> Option<Value> {
+        (self.known == !0).then_some(self.value)
+    }
+
+    fn shr(self, amount: Bits) -> Self {
+        match amount.get() {
+            Some(n) if n >= 64 => Bits::known(0),
+            // shifting in zeros makes the top bits known.
+            Some(n) => Bits {
+                known: self.known >> n | !(u64::MAX >> n),
+                value: self.value >> n,
+            },
+            None => Bits { known: 0, value: 0 },
+        }
+    }
+
+    fn xor(self, other: Bits) -> Self {
+        let known = self.known & other.known;
+        Bits {
+            known,
+            value: (self.value ^ other.value) & known,
+        }
+    }
+
+    fn mod8(self) -> Self {
+        Bits {
+            known: self.known | !7,
+            value: self.value & 7,
+        }
+    }
+
+    fn is_nonzero(self) -> Option<bool> {
+        match (self.value, self.known) {
+            (0, u64::MAX) => Some(false),
+            (0, _) => None,
+            _ => Some(true),
+        }
+    }
+}
+
+/// The partially known state of a run of [`consistent`].
+#[derive(Clone, Copy, Debug)]
+struct State {
+    a: Bits,
+    b: Bits,
+    c: Bits,
+    pc: usize,
+    produced: usize,
+}
+
+/// Whether `cpu` could output `target` if `A` starts with the known bits of `a`. Runs until an output
+/// depends on unknown bits; a jump on an unknown `A` tries both branches.
+fn consistent(cpu: &Cpu, a: Bits, target: &[u8]) -> bool {
+    let state = State {
+        a,
+        b: Bits::known(cpu.registers.b),
+        c: Bits::known(cpu.registers.c),
+        pc: 0,
+        produced: 0,
+    };
+    let mut steps = MAX_STEPS;
+    run_bits(&cpu.program, state, target, &mut steps)
+}
+
+/// Run [`consistent`] from `state`, sharing the budget of `steps` between branches.
+fn run_bits(program: &Program, mut state: State, target: &[u8], steps: &mut usize) -> bool {
+    while *steps > 0 {
+        *steps -= 1;
+        let Some(instruction) = program.instruction(state.pc) else {
+            return state.produced == target.len();
+        };
+        let value = match (instruction.opcode.operand(), instruction.operand) {
+            (Operand::Combo, 4) => state.a,
+            (Operand::Combo, 5) => state.b,
+            (Operand::Combo, 6) => state.c,
+            (Operand::Combo, 7) => return false,
+            (_, operand) => Bits::known(operand.into()),
+        };
+        state.pc += 2;
+
+        match instruction.opcode {
+            Opcode::Adv => state.a = state.a.shr(value),
+            Opcode::Bxl => state.b = state.b.xor(value),
+            Opcode::Bst => state.b = value.mod8(),
+            Opcode::Jnz => match state.a.is_nonzero() {
+                Some(true) => state.pc = usize::from(instruction.operand),
+                Some(false) => {}
+                None => {
+                    if run_bits(program, state, target, steps) {
+                        return true;
+                    }
+                    state.pc = usize::from(instruction.operand);
+                }
+            },
+            Opcode::Bxc => state.b = state.b.xor(state.c),
+            Opcode::Out => {
+                let Some(out) = value.mod8().get() else {
+                    return true;
+                };
+                if target.get(state.produced).map(|&t| Value::from(t)) != Some(out) {
+                    return false;
+                }
+                state.produced += 1;
+            }
+            Opcode::Bdv => state.b = state.a.shr(value),
+            Opcode::Cdv => state.c = state.a.shr(value),
+        }
+    }
+    true
+}
+
+/// Fix the bits of `A` from the lowest up, keeping every prefix that is [`consistent`] with `target`.
+/// All candidates with their highest set bit at position `n` are checked before any longer one,
+/// so the first layer with a solution has the smallest. Fails if a layer has more than [`MAX_FRONTIER`]
+/// prefixes.
+pub fn search_bits(cpu: &Cpu, target: &[u8]) -> Result<Option<Value>, Error> {
+    if outputs(cpu, 0, target) {
+        return Ok(Some(0));
+    }
+
+    let mut layer = vec![0];
+    for bit in 0..64 {
+        let mut next = vec![];
+        let mut best = None;
+        for &prefix in &layer {
+            for a in [prefix, prefix | 1 << bit] {
+                if !consistent(cpu, Bits::low(a, bit + 1), target) {
+                    continue;
+                }
+                if a != prefix && outputs(cpu, a, target) {
+                    best = Some(best.map_or(a, |best: Value| best.min(a)));
+                }
+                next.push(a);
+            }
+        }
+
+        if best.is_some() {
+            return Ok(best);
+        }
+        if next.len() > MAX_FRONTIER {
+            return Err(Error::TooManyCandidates { bit });
+        }
+        layer = next;
+    }
+    Ok(None)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{find_a, find_quine, search_bits, Loop};
+    use crate::computer::{assemble, Cpu, Program, Registers};
+
+    /// A real input, which outputs itself for `A = 247839653009594`.
+    const QUINE: &str =
+        "Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 2,4,1,1,7,5,4,0,0,3,1,6,5,5,3,0\n";
+
+    fn cpu(source: &str) -> Cpu {
+        Cpu::new(assemble(source).unwrap(), Registers::default())
+    }
+
+    fn output(cpu: &Cpu, a: u64) -> Vec<u8> {
+        let mut cpu = cpu.clone();
+        cpu.registers.a = a;
+        cpu.run().unwrap()
+    }
+
+    #[test]
+    fn detects_loops() {
+        let cpu = Cpu::parse(QUINE).unwrap();
+        assert_eq!(Loop::detect(&cpu.program), Some(Loop { shift: 3 }));
+        assert_eq!(
+            Loop::detect(&assemble("adv 2\nout A\njnz 0").unwrap()),
+            Some(Loop { shift: 2 })
+        );
+        // `B` is carried from one pass to the next.
+        assert_eq!(
+            Loop::detect(&assemble("bxl 3\nout B\nadv 3\njnz 0").unwrap()),
+            None
+        );
+        // `A` is shifted by a register.
+        assert_eq!(
+            Loop::detect(&assemble("bst A\nadv B\nout A\njnz 0").unwrap()),
+            None
+        );
+        assert_eq!(Loop::detect(&assemble("adv 3\nout A").unwrap()), None);
+    }
+
+    #[test]
+    fn finds_quines() {
+        let cpu = Cpu::parse(QUINE).unwrap();
+        assert_eq!(find_quine(&cpu), Ok(Some(247_839_653_009_594)));
+        assert_eq!(
+            search_bits(&cpu, cpu.program.words()),
+            Ok(Some(247_839_653_009_594))
+        );
+
+        let example =
+            Cpu::parse("Register A: 2024\nRegister B: 0\nRegister C: 0\n\nProgram: 0,3,5,4,3,0\n")
+                .unwrap();
+        assert_eq!(find_quine(&example), Ok(Some(117_440)));
+        assert_eq!(
+            search_bits(&example, example.program.words()),
+            Ok(Some(117_440))
+        );
+    }
+
+    #[test]
+    fn finds_the_smallest_a() {
+        let programs = [
+            "adv 2\nbst A\nbxl 5\ncdv B\nbxc\nout B\njnz 0",
+            // `B` and `C` are carried between passes, so only the bit search applies.
+            "bxl 3\ncdv 1\nbxc\nout B\nadv 3\njnz 0",
+            "bst A\nadv B\nadv 1\nout A\nbdv 1\nout B\njnz 0",
+        ];
+        for source in programs {
+            let cpu = cpu(source);
+            for a in [1, 0o1234, 0o7654321, 0xdead_beef] {
+                let target = output(&cpu, a);
+                let found = find_a(&cpu, &target).unwrap().unwrap();
+                assert!(found <= a);
+                assert_eq!(output(&cpu, found), target);
+                assert_eq!(search_bits(&cpu, &target), Ok(Some(found)));
+            }
+        }
+        assert_eq!(find_a(&cpu("adv 3\nout A\njnz 0"), &[1, 2]), Ok(None));
+    }
+
+    #[test]
+    fn follows_both_branches_of_unknown_jumps() {
+        // outputs the lowest digit of `A` once per digit and a final 1 after the loop.
+        let cpu = Cpu::new(
+            Program::new(vec![2, 4, 5, 5, 0, 3, 3, 2, 5, 1]).unwrap(),
+            Registers::default(),
+        );
+        assert_eq!(output(&cpu, 5), [5, 1]);
+        assert_eq!(find_a(&cpu, &[5, 1]), Ok(Some(5)));
+        assert_eq!(find_a(&cpu, &[5, 5, 1]), Ok(Some(0o15)));
+        assert_eq!(find_a(&cpu, &[5, 3, 1]), Ok(None));
+    }
+}
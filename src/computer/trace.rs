@@ -0,0 +1,144 @@
+/// Execution traces of the 3-bit computer: every executed instruction with the registers and output after it.
+use std::fmt::Display;
+
+use itertools::Itertools;
+
+use super::{Cpu, Error, Instruction, Registers};
+
+/// One executed instruction and the state after it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub pc: usize,
+    pub instruction: Instruction,
+    pub registers: Registers,
+    pub out: Option<u8>,
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:>3}: {:<6} ; {:<20} {}",
+            self.pc,
+            self.instruction.to_string(),
+            self.instruction.describe(),
+            self.registers
+        )?;
+        if let Some(out) = self.out {
+            write!(f, "  -> {out}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Why a trace stopped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum End {
+    Halted,
+    /// The step limit was reached, e.g. in an infinite loop.
+    Limit,
+    Error(Error),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trace {
+    pub initial: Registers,
+    pub steps: Vec<Step>,
+    pub end: End,
+}
+
+impl Trace {
+    #[must_use]
+    pub fn output(&self) -> Vec<u8> {
+        self.steps.iter().filter_map(|step| step.out).collect()
+    }
+}
+
+impl Display for Trace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:>3}  {:<6}   {:<20} {}", "", "start", "", self.initial)?;
+        for step in &self.steps {
+            writeln!(f, "{step}")?;
+        }
+
+        let steps = self.steps.len();
+        match &self.end {
+            End::Halted => write!(f, "halted after {steps} steps")?,
+            End::Limit => write!(f, "stopped after {steps} steps")?,
+            End::Error(e) => write!(f, "failed after {steps} steps: {e}")?,
+        }
+        write!(f, ", output {}", self.output().iter().join(","))
+    }
+}
+
+impl Cpu {
+    /// Run until the program halts, fails or `max_steps` instructions were executed, recording every step.
+    pub fn trace(&mut self, max_steps: usize) -> Trace {
+        let initial = self.registers;
+        let mut steps = vec![];
+
+        let end = loop {
+            if steps.len() == max_steps {
+                break End::Limit;
+            }
+            match self.step() {
+                Ok(Some(step)) => steps.push(step),
+                Ok(None) => break End::Halted,
+                Err(e) => break End::Error(e),
+            }
+        };
+
+        Trace {
+            initial,
+            steps,
+            end,
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use crate::computer::{assemble, Cpu, End, Error, Registers};
+
+    fn cpu(source: &str, a: u64) -> Cpu {
+        Cpu::new(
+            assemble(source).unwrap(),
+            Registers {
+                a,
+                ..Registers::default()
+            },
+        )
+    }
+
+    #[test]
+    fn records_steps() {
+        let trace = cpu("adv 1\nout A\njnz 0", 729).trace(1000);
+        assert_eq!(trace.end, End::Halted);
+        assert_eq!(trace.steps.len(), 30);
+        assert_eq!(trace.output(), vec![4, 6, 3, 5, 6, 3, 5, 2, 1, 0]);
+
+        let first = trace.steps[0];
+        assert_eq!((first.pc, first.registers.a, first.out), (0, 364, None));
+        assert_eq!(trace.steps[1].out, Some(4));
+        assert_eq!(
+            trace.steps[2].to_string(),
+            "  4: jnz 0  ; if A != 0 jump to 0  A=554 B=0 C=0"
+        );
+    }
+
+    #[test]
+    fn stops_at_the_limit_or_an_error() {
+        let trace = cpu("bxl 1\njnz 0", 1).trace(10);
+        assert_eq!((trace.steps.len(), trace.end), (10, End::Limit));
+
+        let mut cpu = cpu("out A", 0);
+        cpu.program = crate::computer::Program::new(vec![5, 7]).unwrap();
+        let trace = cpu.trace(10);
+        assert_eq!(trace.end, End::Error(Error::ReservedCombo { address: 0 }));
+        assert!(trace
+            .to_string()
+            .ends_with("failed after 0 steps: reserved combo operand 7 at address 0, output "));
+    }
+}
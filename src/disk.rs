@@ -0,0 +1,361 @@
+/// Disks of 2024 day 9 as spans of blocks, compacted block by block or file by file. Free space for whole
+/// files is found with one min-heap of starts per span length, so disk maps millions of digits long compact
+/// in `O(n log n)`.
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::Display;
+
+use crate::parse::{self, Span};
+
+/// Consecutive blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Extent {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Extent {
+    #[must_use]
+    pub fn end(&self) -> usize {
+        self.start + self.len
+    }
+}
+
+/// How [`Disk::compact`] moves data to the free space at the start of the disk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    /// Move single blocks from the end of the disk into the first free block, splitting files.
+    Blocks,
+    /// Move every file once, in order of decreasing id, into the first free span that fits it.
+    Files,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Disk {
+    /// The extents of each file by id, in order on the disk.
+    pub files: Vec<Vec<Extent>>,
+}
+
+impl Disk {
+    /// Read a disk map: digits alternating between the length of a file and the free space after it.
+    pub fn parse(map: &str) -> Result<Disk, parse::Error> {
+        let digits = Span::new(map).cells(|c| c.to_digit(10))?;
+        let mut files = vec![];
+        let mut start = 0;
+        for (i, &len) in digits.iter().enumerate() {
+            let len = len as usize;
+            if i % 2 == 0 {
+                files.push(vec![Extent { start, len }]);
+            }
+            start += len;
+        }
+        Ok(Disk { files })
+    }
+
+    /// Every extent of every file with its id, in order on the disk.
+    fn extents(&self) -> Vec<(usize, Extent)> {
+        let mut extents: Vec<_> = self
+            .files
+            .iter()
+            .enumerate()
+            .flat_map(|(id, extents)| extents.iter().map(move |&extent| (id, extent)))
+            .filter(|(_, extent)| extent.len > 0)
+            .collect();
+        extents.sort_unstable_by_key(|(_, extent)| extent.start);
+        extents
+    }
+
+    /// The free spans between the first block of the disk and the last used one.
+    #[must_use]
+    pub fn free(&self) -> Vec<Extent> {
+        let mut free = vec![];
+        let mut end = 0;
+        for (_, extent) in self.extents() {
+            if extent.start > end {
+                free.push(Extent {
+                    start: end,
+                    len: extent.start - end,
+                });
+            }
+            end = extent.end();
+        }
+        free
+    }
+
+    /// The sum of each block's position times the id of the file in it.
+    #[must_use]
+    pub fn checksum(&self) -> usize {
+        self.files
+            .iter()
+            .enumerate()
+            .flat_map(|(id, extents)| extents.iter().map(move |extent| (id, extent)))
+            .map(|(id, extent)| id * (extent.start..extent.end()).sum::<usize>())
+            .sum()
+    }
+
+    /// The file id in each block up to the last used one.
+    #[must_use]
+    pub fn blocks(&self) -> Vec<Option<usize>> {
+        let extents = self.extents();
+        let size = extents.last().map_or(0, |(_, extent)| extent.end());
+        let mut blocks = vec![None; size];
+        for (id, extent) in extents {
+            blocks[extent.start..extent.end()].fill(Some(id));
+        }
+        blocks
+    }
+
+    pub fn compact(&mut self, strategy: Strategy) -> Report {
+        let before = self.clone();
+        match strategy {
+            Strategy::Blocks => self.compact_blocks(),
+            Strategy::Files => self.compact_files(),
+        }
+        Report::new(strategy, &before, self)
+    }
+
+    /// Fill the free spans from the left with the blocks of the rightmost extents.
+    fn compact_blocks(&mut self) {
+        let free = self.free();
+        let mut extents = self.extents();
+        for extents in &mut self.files {
+            extents.clear();
+        }
+
+        let mut free = free.into_iter().peekable();
+        while let Some((id, mut extent)) = extents.pop() {
+            while let Some(space) = free.peek_mut().filter(|space| space.start < extent.start) {
+                let moved = space.len.min(extent.len);
+                self.files[id].push(Extent {
+                    start: space.start,
+                    len: moved,
+                });
+                (space.start, space.len) = (space.start + moved, space.len - moved);
+                extent.len -= moved;
+                if space.len == 0 {
+                    free.next();
+                }
+                if extent.len == 0 {
+                    break;
+                }
+            }
+            if extent.len > 0 {
+                self.files[id].push(extent);
+            }
+        }
+
+        for extents in &mut self.files {
+            extents.sort_unstable_by_key(|extent| extent.start);
+        }
+    }
+
+    /// Move files to the first free span left of them that is long enough. Space freed by a move is not
+    /// reused, no file with a lower id could move there on a disk read from a map.
+    fn compact_files(&mut self) {
+        let free = self.free();
+        let longest = free.iter().map(|space| space.len).max().unwrap_or(0);
+        let mut starts: Vec<BinaryHeap<Reverse<usize>>> = vec![BinaryHeap::new(); longest + 1];
+        for space in free {
+            starts[space.len].push(Reverse(space.start));
+        }
+
+        for extents in self.files.iter_mut().rev() {
+            let Some(first) = extents.first().map(|extent| extent.start) else {
+                continue;
+            };
+            let len: usize = extents.iter().map(|extent| extent.len).sum();
+            if len == 0 {
+                continue;
+            }
+
+            let best = (len..=longest)
+                .filter_map(|space_len| {
+                    starts[space_len]
+                        .peek()
+                        .map(|&Reverse(start)| (start, space_len))
+                })
+                .filter(|&(start, _)| start < first)
+                .min();
+            if let Some((start, space_len)) = best {
+                starts[space_len].pop();
+                if space_len > len {
+                    starts[space_len - len].push(Reverse(start + len));
+                }
+                *extents = vec![Extent { start, len }];
+            }
+        }
+    }
+}
+
+/// Blocks as digits of their file id modulo 10 and free ones as `.`, like the puzzle.
+impl Display for Disk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for block in self.blocks() {
+            match block {
+                Some(id) => write!(f, "{}", id % 10)?,
+                None => write!(f, ".")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// What a compaction changed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Report {
+    pub strategy: Strategy,
+    /// Files with at least one moved block.
+    pub moved_files: usize,
+    pub moved_blocks: usize,
+    /// Files in more than one extent after compacting.
+    pub fragmented_files: usize,
+    /// Free spans between used blocks before and after compacting.
+    pub free_spans: (usize, usize),
+    pub checksum: usize,
+}
+
+impl Report {
+    fn new(strategy: Strategy, before: &Disk, after: &Disk) -> Self {
+        let mut moved_files = 0;
+        let mut moved_blocks = 0;
+        for (old, new) in before.files.iter().zip(&after.files) {
+            let moved = new
+                .iter()
+                .map(|extent| extent.len - old.iter().map(|o| overlap(*extent, *o)).sum::<usize>())
+                .sum::<usize>();
+            moved_files += usize::from(moved > 0);
+            moved_blocks += moved;
+        }
+
+        Report {
+            strategy,
+            moved_files,
+            moved_blocks,
+            fragmented_files: after
+                .files
+                .iter()
+                .filter(|extents| extents.len() > 1)
+                .count(),
+            free_spans: (before.free().len(), after.free().len()),
+            checksum: after.checksum(),
+        }
+    }
+}
+
+/// The number of blocks in both extents.
+fn overlap(a: Extent, b: Extent) -> usize {
+    a.end().min(b.end()).saturating_sub(a.start.max(b.start))
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?}: moved {} blocks of {} files, {} files fragmented, free spans {} -> {}, checksum {}",
+            self.strategy,
+            self.moved_blocks,
+            self.moved_files,
+            self.fragmented_files,
+            self.free_spans.0,
+            self.free_spans.1,
+            self.checksum
+        )
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{Disk, Extent, Strategy};
+
+    const EXAMPLE: &str = "2333133121414131402\n";
+
+    /// Compact a block array one block or file at a time, like the puzzle describes it.
+    fn naive(map: &str, strategy: Strategy) -> usize {
+        let mut blocks = Disk::parse(map).unwrap().blocks();
+        match strategy {
+            Strategy::Blocks => {
+                while let Some(free) = blocks.iter().position(Option::is_none) {
+                    let last = blocks.iter().rposition(Option::is_some).unwrap();
+                    if free > last {
+                        break;
+                    }
+                    blocks.swap(free, last);
+                }
+            }
+            Strategy::Files => {
+                let files = blocks.iter().flatten().max().map_or(0, |&id| id + 1);
+                for id in (0..files).rev() {
+                    let Some(start) = blocks.iter().position(|&b| b == Some(id)) else {
+                        continue;
+                    };
+                    let len = blocks.iter().filter(|&&b| b == Some(id)).count();
+                    let space = (0..start)
+                        .filter(|&i| i + len <= start)
+                        .find(|&i| blocks[i..i + len].iter().all(Option::is_none));
+                    if let Some(space) = space {
+                        for i in 0..len {
+                            blocks.swap(space + i, start + i);
+                        }
+                    }
+                }
+            }
+        }
+        blocks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, b)| b.map(|id| i * id))
+            .sum()
+    }
+
+    #[test]
+    fn parses_maps() {
+        let disk = Disk::parse("12345").unwrap();
+        assert_eq!(disk.to_string(), "0..111....22222");
+        assert_eq!(disk.files[1], vec![Extent { start: 3, len: 3 }]);
+        assert_eq!(
+            disk.free(),
+            vec![Extent { start: 1, len: 2 }, Extent { start: 6, len: 4 }]
+        );
+        assert_eq!(Disk::parse("12x").unwrap_err().column, 3);
+    }
+
+    #[test]
+    fn compacts_blocks() {
+        let mut disk = Disk::parse(EXAMPLE).unwrap();
+        let report = disk.compact(Strategy::Blocks);
+        assert_eq!(disk.to_string(), "0099811188827773336446555566");
+        assert_eq!(report.checksum, 1928);
+        assert_eq!(report.free_spans, (8, 0));
+    }
+
+    #[test]
+    fn compacts_files() {
+        let mut disk = Disk::parse(EXAMPLE).unwrap();
+        let report = disk.compact(Strategy::Files);
+        assert_eq!(disk.to_string(), "00992111777.44.333....5555.6666.....8888");
+        assert_eq!(report.checksum, 2858);
+        assert_eq!((report.moved_files, report.moved_blocks), (4, 8));
+        assert_eq!(report.fragmented_files, 0);
+        assert_eq!(
+            report.to_string(),
+            "Files: moved 8 blocks of 4 files, 0 files fragmented, free spans 8 -> 5, checksum 2858"
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn matches_naive_compaction(map in "[0-9]{1,40}") {
+            for strategy in [Strategy::Blocks, Strategy::Files] {
+                let mut disk = Disk::parse(&map).unwrap();
+                let report = disk.compact(strategy);
+                prop_assert_eq!(report.checksum, naive(&map, strategy));
+            }
+        }
+    }
+}
@@ -0,0 +1,413 @@
+/// Search for operators between numbers that make them evaluate to a goal, like `190: 10 19` in 2024 day 7,
+/// which `10 * 19` solves. Operators are plain values with checked arithmetic, so adding one is a `const`.
+/// With equal precedences the search works backwards from the goal, undoing the last number.
+use std::fmt::Display;
+
+use itertools::Itertools;
+
+pub type Value = u64;
+
+/// The left operand `a` for which `a op b` is a given result.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Inverse {
+    Impossible,
+    Exactly(Value),
+    /// Every `a` works, e.g. for `a * 0 = 0`.
+    Any,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Operator {
+    pub symbol: &'static str,
+    /// `a op b`, `None` if it overflows.
+    pub apply: fn(Value, Value) -> Option<Value>,
+    /// Undo `a op b = result`, given `result` and `b`.
+    pub undo: fn(Value, Value) -> Inverse,
+    /// Operators with a higher precedence are applied first, equal ones left to right.
+    pub precedence: u8,
+    /// Whether `a op b` is at least `a` and `b` for positive operands, which lets the search stop as soon
+    /// as a partial value exceeds the goal.
+    pub monotone: bool,
+}
+
+impl Operator {
+    #[must_use]
+    pub const fn with_precedence(self, precedence: u8) -> Self {
+        Operator { precedence, ..self }
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbol)
+    }
+}
+
+pub const ADD: Operator = Operator {
+    symbol: "+",
+    apply: Value::checked_add,
+    undo: |result, b| match result.checked_sub(b) {
+        Some(a) => Inverse::Exactly(a),
+        None => Inverse::Impossible,
+    },
+    precedence: 0,
+    monotone: true,
+};
+
+pub const MUL: Operator = Operator {
+    symbol: "*",
+    apply: Value::checked_mul,
+    undo: |result, b| match (result, b) {
+        (0, 0) => Inverse::Any,
+        (_, 0) => Inverse::Impossible,
+        _ if result % b == 0 => Inverse::Exactly(result / b),
+        _ => Inverse::Impossible,
+    },
+    precedence: 0,
+    monotone: true,
+};
+
+/// The digits of `a` followed by the digits of `b`: `12 || 345 = 12345`.
+pub const CONCAT: Operator = Operator {
+    symbol: "||",
+    apply: |a, b| a.checked_mul(digits_shift(b)?)?.checked_add(b),
+    undo: |result, b| match digits_shift(b) {
+        Some(shift) if result % shift == b => Inverse::Exactly(result / shift),
+        _ => Inverse::Impossible,
+    },
+    precedence: 0,
+    monotone: true,
+};
+
+/// The power of ten to shift a number by to append `b`, `None` if that does not fit.
+fn digits_shift(b: Value) -> Option<Value> {
+    10u64.checked_pow(b.checked_ilog10().unwrap_or(0) + 1)
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The result of [`Equation::solve`].
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    /// The operators between the numbers of one solution.
+    pub operators: Option<Vec<Operator>>,
+    /// How many partial equations were considered.
+    pub explored: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Equation {
+    pub goal: Value,
+    pub nums: Vec<Value>,
+}
+
+impl Equation {
+    /// Find operators that make `nums` evaluate to `goal`, trying `operators` in order.
+    #[must_use]
+    pub fn solve(&self, operators: &[Operator]) -> Outcome {
+        let mut path = vec![];
+        let mut explored = 0;
+
+        if self.nums.is_empty() {
+            return Outcome {
+                operators: None,
+                explored,
+            };
+        }
+
+        let found = if operators.iter().map(|op| op.precedence).all_equal() {
+            let n = self.nums.len();
+            self.backward(operators, n, self.goal, &mut path, &mut explored)
+        } else {
+            // a zero can make even monotone operators decrease, e.g. `a * 0`.
+            let monotone =
+                operators.iter().all(|op| op.monotone) && self.nums.iter().all(|&num| num > 0);
+            let search = Forward {
+                equation: self,
+                operators,
+                monotone,
+            };
+            search.forward(1, &[], self.nums[0], &mut path, &mut explored)
+        };
+
+        Outcome {
+            operators: found.then_some(path),
+            explored,
+        }
+    }
+
+    /// Evaluate the numbers with `operators` between them, `None` if it overflows.
+    /// Panics unless there is one operator less than numbers.
+    #[must_use]
+    pub fn eval(nums: &[Value], operators: &[Operator]) -> Option<Value> {
+        let (&first, rest) = nums.split_first()?;
+        let mut stack = vec![];
+        let mut current = first;
+        for (&op, &num) in operators.iter().zip_eq(rest) {
+            push(&mut stack, current, op)?;
+            current = num;
+        }
+        reduce(&stack, current)
+    }
+
+    /// The solved equation, e.g. `190 = 10 * 19`.
+    #[must_use]
+    pub fn show(&self, operators: &[Operator]) -> String {
+        let mut expression = self.nums.iter().map(Value::to_string);
+        let first = expression.next().unwrap_or_default();
+        let rest = operators
+            .iter()
+            .zip(expression)
+            .map(|(op, num)| format!(" {op} {num}"))
+            .join("");
+        format!("{} = {first}{rest}", self.goal)
+    }
+
+    /// Left to right: the first `n` numbers must evaluate to `goal`, so the last of them was combined with
+    /// a left side that can be computed by undoing the operator.
+    fn backward(
+        &self,
+        operators: &[Operator],
+        n: usize,
+        goal: Value,
+        path: &mut Vec<Operator>,
+        explored: &mut usize,
+    ) -> bool {
+        *explored += 1;
+        if n == 1 {
+            return self.nums[0] == goal;
+        }
+
+        let b = self.nums[n - 1];
+        for &op in operators {
+            let found = match (op.undo)(goal, b) {
+                Inverse::Impossible => false,
+                Inverse::Exactly(a) => self.backward(operators, n - 1, a, path, explored),
+                Inverse::Any => self.any(operators, n - 1, path, explored),
+            };
+            if found {
+                path.push(op);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Left to right: whether the first `n` numbers evaluate to anything without overflowing.
+    fn any(
+        &self,
+        operators: &[Operator],
+        n: usize,
+        path: &mut Vec<Operator>,
+        explored: &mut usize,
+    ) -> bool {
+        fn go(
+            nums: &[Value],
+            operators: &[Operator],
+            current: Value,
+            path: &mut Vec<Operator>,
+            explored: &mut usize,
+        ) -> bool {
+            *explored += 1;
+            let Some((&num, rest)) = nums.split_first() else {
+                return true;
+            };
+            for &op in operators {
+                if let Some(next) = (op.apply)(current, num) {
+                    path.push(op);
+                    if go(rest, operators, next, path, explored) {
+                        return true;
+                    }
+                    path.pop();
+                }
+            }
+            false
+        }
+
+        go(&self.nums[1..n], operators, self.nums[0], path, explored)
+    }
+}
+
+impl Display for Equation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.goal, self.nums.iter().join(" "))
+    }
+}
+
+/// Append `current op`, applying pending operators that bind at least as strong as `op` first.
+fn push(stack: &mut Vec<(Value, Operator)>, mut current: Value, op: Operator) -> Option<()> {
+    while let Some(&(left, pending)) = stack.last() {
+        if pending.precedence < op.precedence {
+            break;
+        }
+        current = (pending.apply)(left, current)?;
+        stack.pop();
+    }
+    stack.push((current, op));
+    Some(())
+}
+
+/// Apply all pending operators to `current`.
+fn reduce(stack: &[(Value, Operator)], current: Value) -> Option<Value> {
+    stack
+        .iter()
+        .rev()
+        .try_fold(current, |current, &(left, op)| (op.apply)(left, current))
+}
+
+/// A search from the first number on, for operators with different precedences.
+struct Forward<'a> {
+    equation: &'a Equation,
+    operators: &'a [Operator],
+    /// Whether appending numbers never makes the value smaller, so a partial value above the goal fails.
+    monotone: bool,
+}
+
+impl Forward<'_> {
+    fn forward(
+        &self,
+        i: usize,
+        stack: &[(Value, Operator)],
+        current: Value,
+        path: &mut Vec<Operator>,
+        explored: &mut usize,
+    ) -> bool {
+        *explored += 1;
+        let nums = &self.equation.nums;
+        let value = reduce(stack, current);
+        if i == nums.len() {
+            return value == Some(self.equation.goal);
+        }
+        if self.monotone && value.is_none_or(|value| value > self.equation.goal) {
+            return false;
+        }
+
+        for &op in self.operators {
+            let mut stack = stack.to_vec();
+            if push(&mut stack, current, op).is_none() {
+                continue;
+            }
+            path.push(op);
+            if self.forward(i + 1, &stack, nums[i], path, explored) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Equation, Inverse, Operator, Value, ADD, CONCAT, MUL};
+
+    fn equation(goal: Value, nums: &[Value]) -> Equation {
+        Equation {
+            goal,
+            nums: nums.to_vec(),
+        }
+    }
+
+    fn solve(equation: &Equation, operators: &[Operator]) -> Option<String> {
+        let operators = equation.solve(operators).operators?;
+        assert_eq!(
+            Equation::eval(&equation.nums, &operators),
+            Some(equation.goal)
+        );
+        Some(equation.show(&operators))
+    }
+
+    #[test]
+    fn applies_and_undoes_operators() {
+        assert_eq!((CONCAT.apply)(12, 345), Some(12345));
+        assert_eq!((CONCAT.apply)(12, 0), Some(120));
+        assert_eq!((CONCAT.apply)(Value::MAX / 10, 10), None);
+        assert_eq!((CONCAT.undo)(12345, 345), Inverse::Exactly(12));
+        assert_eq!((CONCAT.undo)(12345, 45), Inverse::Exactly(123));
+        assert_eq!((CONCAT.undo)(12345, 5), Inverse::Exactly(1234));
+        assert_eq!((CONCAT.undo)(12345, 6), Inverse::Impossible);
+        assert_eq!((MUL.apply)(Value::MAX, 2), None);
+        assert_eq!((MUL.undo)(0, 0), Inverse::Any);
+        assert_eq!((MUL.undo)(12, 5), Inverse::Impossible);
+        assert_eq!((ADD.undo)(3, 5), Inverse::Impossible);
+    }
+
+    #[test]
+    fn finds_left_to_right_witnesses() {
+        let part_one = [ADD, MUL];
+        let part_two = [ADD, MUL, CONCAT];
+        assert_eq!(
+            solve(&equation(190, &[10, 19]), &part_one),
+            Some("190 = 10 * 19".into())
+        );
+        assert_eq!(
+            solve(&equation(292, &[11, 6, 16, 20]), &part_one),
+            Some("292 = 11 + 6 * 16 + 20".into())
+        );
+        assert_eq!(solve(&equation(156, &[15, 6]), &part_one), None);
+        assert_eq!(
+            solve(&equation(7290, &[6, 8, 6, 15]), &part_two),
+            Some("7290 = 6 * 8 || 6 * 15".into())
+        );
+        assert_eq!(
+            solve(&equation(0, &[7, 3, 0]), &part_one),
+            Some("0 = 7 + 3 * 0".into())
+        );
+        assert_eq!(solve(&equation(5, &[]), &part_one), None);
+
+        // overflowing would wrap around to the goal
+        assert_eq!(solve(&equation(0, &[1 << 32, 1 << 32]), &[MUL]), None);
+    }
+
+    #[test]
+    fn respects_precedence() {
+        let operators = [ADD, MUL.with_precedence(1)];
+        assert_eq!(
+            Equation::eval(&[2, 3, 4], &[ADD, MUL.with_precedence(1)]),
+            Some(14)
+        );
+        assert_eq!(
+            Equation::eval(&[2, 3, 4], &[MUL.with_precedence(1), ADD]),
+            Some(10)
+        );
+        assert_eq!(Equation::eval(&[2, 3, 4], &[ADD, MUL]), Some(20));
+        assert_eq!(
+            solve(&equation(14, &[2, 3, 4]), &operators),
+            Some("14 = 2 + 3 * 4".into())
+        );
+        assert_eq!(solve(&equation(20, &[2, 3, 4]), &operators), None);
+        assert_eq!(
+            solve(&equation(20, &[2, 3, 4]), &[ADD, MUL]),
+            Some("20 = 2 + 3 * 4".into())
+        );
+        assert_eq!(solve(&equation(0, &[1 << 32, 1 << 32]), &operators), None);
+
+        // the partial value `5 * 3` exceeds the goal, so it must not be pruned.
+        let sub = Operator {
+            symbol: "-",
+            apply: Value::checked_sub,
+            undo: |result, b| match result.checked_add(b) {
+                Some(a) => Inverse::Exactly(a),
+                None => Inverse::Impossible,
+            },
+            precedence: 0,
+            monotone: false,
+        };
+        assert_eq!(
+            solve(&equation(5, &[5, 3, 10]), &[sub, MUL.with_precedence(1)]),
+            Some("5 = 5 * 3 - 10".into())
+        );
+    }
+
+    #[test]
+    fn counts_explored_states() {
+        let equation = equation(3267, &[81, 40, 27]);
+        let backward = equation.solve(&[ADD, MUL]);
+        let forward = equation.solve(&[ADD, MUL.with_precedence(1)]);
+        assert!(backward.operators.is_some());
+        assert_eq!(backward.explored, 4);
+        assert!(forward.explored > backward.explored);
+    }
+}
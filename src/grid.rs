@@ -0,0 +1,230 @@
+/// A rectangular 2D grid indexed by [`Point`], as used by most grid puzzles.
+/// Rows are stored top to bottom, so [`UP`] points towards `y - 1`.
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use lina::{point2, vec2, Point2, Vec2};
+
+pub type Point = Point2<i32>;
+
+pub const UP: Vec2<i32> = vec2(0, -1);
+pub const RIGHT: Vec2<i32> = vec2(1, 0);
+pub const DOWN: Vec2<i32> = vec2(0, 1);
+pub const LEFT: Vec2<i32> = vec2(-1, 0);
+
+/// The orthogonal directions, clockwise starting at [`UP`].
+pub const UP_RIGHT_DOWN_LEFT: [Vec2<i32>; 4] = [UP, RIGHT, DOWN, LEFT];
+
+/// Index of an orthogonal unit vector in [`UP_RIGHT_DOWN_LEFT`].
+#[must_use]
+pub fn orthogonal_to_index(direction: Vec2<i32>) -> Option<usize> {
+    UP_RIGHT_DOWN_LEFT.iter().position(|&d| d == direction)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T>(pub Vec<Vec<T>>);
+
+impl<T> Grid<T> {
+    #[must_use]
+    pub fn new(rows: Vec<Vec<T>>) -> Self {
+        Grid(rows)
+    }
+
+    /// Read a grid with one cell per character, skipping empty lines.
+    #[must_use]
+    pub fn read(input: &str, f: impl Fn(char) -> T) -> Self {
+        Grid(
+            input
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(|line| line.chars().map(&f).collect())
+                .collect(),
+        )
+    }
+
+    /// Build a grid of `dimension` from a function of each point.
+    #[must_use]
+    pub fn new_with_dimensions(dimension: Vec2<i32>, f: impl Fn(Point) -> T) -> Self {
+        Grid(
+            (0..dimension.y)
+                .map(|y| (0..dimension.x).map(|x| f(point2(x, y))).collect())
+                .collect(),
+        )
+    }
+
+    #[must_use]
+    pub fn new_with_dimensions_uniform(dimension: Vec2<i32>, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::new_with_dimensions(dimension, |_| value.clone())
+    }
+
+    /// Width and height of the grid.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    #[must_use]
+    pub fn dimension(&self) -> Vec2<i32> {
+        vec2(
+            self.0.first().map_or(0, Vec::len) as i32,
+            self.0.len() as i32,
+        )
+    }
+
+    #[must_use]
+    pub fn contains(&self, p: Point) -> bool {
+        let dimension = self.dimension();
+        (0..dimension.x).contains(&p.x) && (0..dimension.y).contains(&p.y)
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    #[must_use]
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.contains(p)
+            .then(|| &self.0[p.y as usize][p.x as usize])
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        if self.contains(p) {
+            Some(&mut self.0[p.y as usize][p.x as usize])
+        } else {
+            None
+        }
+    }
+
+    /// All points of the grid, row by row.
+    pub fn iter_coordinates(&self) -> impl Iterator<Item = Point> {
+        let dimension = self.dimension();
+        (0..dimension.y).flat_map(move |y| (0..dimension.x).map(move |x| point2(x, y)))
+    }
+
+    /// The first point, row by row, whose cell matches `predicate`.
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Point> {
+        self.iter_coordinates().find(|&p| predicate(&self[p]))
+    }
+
+    /// The orthogonal neighbours of `p` that are inside the grid, clockwise starting above it.
+    pub fn neighbours(&self, p: Point) -> impl Iterator<Item = (Point, &T)> {
+        UP_RIGHT_DOWN_LEFT
+            .iter()
+            .filter_map(move |&d| self.get(p + d).map(|cell| (p + d, cell)))
+    }
+
+    #[must_use]
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid(
+            self.0
+                .iter()
+                .map(|row| row.iter().map(&f).collect())
+                .collect(),
+        )
+    }
+
+    /// Convert every cell to the character it is drawn as.
+    #[must_use]
+    pub fn char(&self) -> Grid<char>
+    where
+        T: Copy + Into<char>,
+    {
+        self.map(|&x| x.into())
+    }
+
+    /// Draw the grid with one character per cell.
+    #[must_use]
+    pub fn display(&self) -> String
+    where
+        T: Copy + Into<char>,
+    {
+        self.0
+            .iter()
+            .map(|row| row.iter().map(|&x| x.into()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Print every row with the cells' `Display` output side by side.
+    pub fn print(&self)
+    where
+        T: Display,
+    {
+        for row in &self.0 {
+            println!(
+                "{}",
+                row.iter().map(ToString::to_string).collect::<String>()
+            );
+        }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &Self::Output {
+        self.get(p)
+            .unwrap_or_else(|| panic!("point {p:?} is out of bounds"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut Self::Output {
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("point {p:?} is out of bounds"))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use lina::{point2, vec2};
+
+    use super::{orthogonal_to_index, Grid, DOWN, LEFT, UP, UP_RIGHT_DOWN_LEFT};
+
+    const INPUT: &str = "S.#\n.##\n..E\n";
+
+    #[test]
+    fn reads_grids() {
+        let grid = Grid::read(INPUT, |c| c);
+        assert_eq!(grid.dimension(), vec2(3, 3));
+        assert_eq!(grid[point2(2, 0)], '#');
+        assert_eq!(grid.position(|&c| c == 'E'), Some(point2(2, 2)));
+        assert_eq!(grid.display(), INPUT.trim_end());
+    }
+
+    #[test]
+    fn checks_bounds() {
+        let grid = Grid::read(INPUT, |c| c);
+        assert!(grid.contains(point2(0, 0)));
+        assert!(!grid.contains(point2(-1, 0)));
+        assert!(!grid.contains(point2(0, 3)));
+        assert_eq!(grid.get(point2(3, 0)), None);
+    }
+
+    #[test]
+    fn finds_neighbours() {
+        let grid = Grid::read(INPUT, |c| c);
+        let neighbours: Vec<_> = grid.neighbours(point2(0, 0)).collect();
+        assert_eq!(neighbours, vec![(point2(1, 0), &'.'), (point2(0, 1), &'.')]);
+        assert_eq!(grid.neighbours(point2(1, 1)).count(), 4);
+    }
+
+    #[test]
+    fn builds_and_maps_grids() {
+        let grid = Grid::new_with_dimensions(vec2(3, 2), |p| p.x + p.y * 10);
+        assert_eq!(grid.0, vec![vec![0, 1, 2], vec![10, 11, 12]]);
+        assert_eq!(grid.map(|&x| x > 10).0[1], vec![false, true, true]);
+
+        let mut uniform = Grid::new_with_dimensions_uniform(vec2(2, 2), 0);
+        uniform[point2(1, 1)] += 1;
+        assert_eq!(uniform.0, vec![vec![0, 0], vec![0, 1]]);
+        assert_eq!(uniform.iter_coordinates().count(), 4);
+    }
+
+    #[test]
+    fn indexes_directions() {
+        assert_eq!(UP_RIGHT_DOWN_LEFT[orthogonal_to_index(DOWN).unwrap()], DOWN);
+        assert_eq!(orthogonal_to_index(UP + LEFT), None);
+    }
+}
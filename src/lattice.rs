@@ -0,0 +1,212 @@
+/// Geometry of grid points on lines: steps reduced by their gcd, every point on the line through two
+/// points and the points at a ratio of distances to two points, like the antinodes of 2024 day 8.
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use itertools::Itertools;
+use lina::{point2, vec2, Vec2};
+
+use crate::grid::Point;
+
+/// Greatest common divisor, never negative. `gcd(0, 0) == 0`.
+#[must_use]
+pub fn gcd(a: i32, b: i32) -> i32 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The smallest step in the direction of `v` that stays on the grid, e.g. `(2, 1)` for `(4, 2)`.
+#[must_use]
+pub fn primitive(v: Vec2<i32>) -> Vec2<i32> {
+    match gcd(v.x, v.y) {
+        0 => v,
+        d => vec2(v.x / d, v.y / d),
+    }
+}
+
+/// All grid points on the line through `a` and `b` for which `contains` holds, in order along the line.
+/// `contains` must describe a convex area like a [`Grid`](crate::grid::Grid) containing `a`.
+pub fn line(a: Point, b: Point, contains: impl Fn(Point) -> bool) -> Vec<Point> {
+    let step = primitive(b - a);
+    if step == vec2(0, 0) {
+        return vec![a].into_iter().filter(|&p| contains(p)).collect();
+    }
+
+    let mut start = a;
+    while contains(start - step) {
+        start -= step;
+    }
+    let mut points = vec![];
+    let mut p = start;
+    while contains(p) {
+        points.push(p);
+        p += step;
+    }
+    points
+}
+
+/// One point is `far / near` times as far from one antenna as from the other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Ratio {
+    pub far: i32,
+    pub near: i32,
+}
+
+impl Ratio {
+    /// Twice as far from one as from the other.
+    pub const DOUBLE: Ratio = Ratio { far: 2, near: 1 };
+}
+
+/// The grid points on the line through `a` and `b` whose distances to `a` and `b` have the given ratio,
+/// in either order. Points between `a` and `b` are only included if `between` is set.
+/// Both parts of the ratio must be positive.
+#[must_use]
+pub fn at_ratio(a: Point, b: Point, ratio: Ratio, between: bool) -> Vec<Point> {
+    let (m, n) = (ratio.far.max(ratio.near), ratio.far.min(ratio.near));
+    assert!(n > 0, "both parts of a ratio must be positive");
+    let d = b - a;
+
+    // points are `a + s * d`, with `|s| : |s - 1|` being `m : n` or `n : m`.
+    let mut fractions = vec![];
+    if m != n {
+        fractions.extend([(m, m - n), (-n, m - n)]);
+    }
+    if between {
+        fractions.extend([(n, m + n), (m, m + n)]);
+    }
+
+    fractions
+        .into_iter()
+        .filter(|&(num, den)| (num * d.x) % den == 0 && (num * d.y) % den == 0)
+        .map(|(num, den)| point2(a.x + num * d.x / den, a.y + num * d.y / den))
+        .unique()
+        .collect()
+}
+
+/// The points `pair` finds for every two distinct points of a group, per group.
+pub fn antinodes<K, I>(
+    groups: &HashMap<K, Vec<Point>>,
+    pair: impl Fn(Point, Point) -> I,
+) -> HashMap<K, HashSet<Point>>
+where
+    K: Copy + Eq + Hash,
+    I: IntoIterator<Item = Point>,
+{
+    groups
+        .iter()
+        .map(|(&key, points)| {
+            let found = points
+                .iter()
+                .tuple_combinations()
+                .filter(|(a, b)| a != b)
+                .flat_map(|(&a, &b)| pair(a, b))
+                .collect();
+            (key, found)
+        })
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::collections::HashMap;
+
+    use lina::{point2, vec2};
+    use proptest::prelude::*;
+
+    use super::{antinodes, at_ratio, gcd, line, primitive, Ratio};
+    use crate::grid::Point;
+
+    const SIZE: i32 = 12;
+
+    fn on_grid(p: Point) -> bool {
+        (0..SIZE).contains(&p.x) && (0..SIZE).contains(&p.y)
+    }
+
+    fn point() -> impl Strategy<Value = Point> {
+        (0..SIZE, 0..SIZE).prop_map(|(x, y)| point2(x, y))
+    }
+
+    fn collinear(p: Point, a: Point, b: Point) -> bool {
+        let (u, v) = (p - a, b - a);
+        u.x * v.y == u.y * v.x
+    }
+
+    fn distance_squared(p: Point, q: Point) -> i32 {
+        let d = p - q;
+        d.x * d.x + d.y * d.y
+    }
+
+    #[test]
+    fn reduces_steps() {
+        assert_eq!(gcd(12, -18), 6);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(primitive(vec2(4, 2)), vec2(2, 1));
+        assert_eq!(primitive(vec2(0, -3)), vec2(0, -1));
+        assert_eq!(primitive(vec2(-6, 9)), vec2(-2, 3));
+    }
+
+    #[test]
+    fn finds_points_the_raw_difference_misses() {
+        let points = line(point2(0, 0), point2(4, 2), on_grid);
+        assert_eq!(points, (0..6).map(|i| point2(2 * i, i)).collect::<Vec<_>>());
+        assert_eq!(
+            at_ratio(point2(3, 3), point2(6, 9), Ratio::DOUBLE, true),
+            vec![point2(9, 15), point2(0, -3), point2(4, 5), point2(5, 7)]
+        );
+        assert_eq!(
+            at_ratio(point2(0, 0), point2(2, 2), Ratio { far: 1, near: 1 }, true),
+            vec![point2(1, 1)]
+        );
+    }
+
+    #[test]
+    fn groups_antinodes() {
+        let groups = HashMap::from([
+            ('a', vec![point2(4, 3), point2(5, 5)]),
+            ('A', vec![point2(1, 1)]),
+        ]);
+        let found = antinodes(&groups, |a, b| at_ratio(a, b, Ratio::DOUBLE, false));
+        assert_eq!(found[&'a'], [point2(6, 7), point2(3, 1)].into());
+        assert!(found[&'A'].is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn line_matches_brute_force(a in point(), b in point()) {
+            prop_assume!(a != b);
+            let found = line(a, b, on_grid);
+            let expected: Vec<Point> = (0..SIZE)
+                .flat_map(|x| (0..SIZE).map(move |y| point2(x, y)))
+                .filter(|&p| collinear(p, a, b))
+                .collect();
+            prop_assert_eq!(found.len(), expected.len());
+            prop_assert!(expected.iter().all(|p| found.contains(p)));
+        }
+
+        #[test]
+        fn ratio_matches_brute_force(a in point(), b in point(), far in 1..5, near in 1..5, between: bool) {
+            prop_assume!(a != b);
+            let found = at_ratio(a, b, Ratio { far, near }, between);
+            let window = 5 * SIZE;
+            let expected: Vec<Point> = (-window..=window)
+                .flat_map(|x| (-window..=window).map(move |y| point2(x, y)))
+                .filter(|&p| collinear(p, a, b))
+                .filter(|&p| {
+                    let (to_a, to_b) = (distance_squared(p, a), distance_squared(p, b));
+                    near * near * to_a == far * far * to_b || far * far * to_a == near * near * to_b
+                })
+                .filter(|&p| {
+                    let (u, v) = (p - a, p - b);
+                    between || u.x * v.x + u.y * v.y > 0
+                })
+                .collect();
+            prop_assert_eq!(found.len(), expected.len());
+            prop_assert!(expected.iter().all(|p| found.contains(p)));
+        }
+    }
+}
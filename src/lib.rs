@@ -1 +1,13 @@
+pub mod computer;
+pub mod disk;
+pub mod equation;
+pub mod grid;
+pub mod lattice;
+pub mod linear;
+pub mod parse;
+pub mod region;
+pub mod search;
+pub mod stones;
 pub mod template;
+pub mod trails;
+pub mod visualize;
@@ -0,0 +1,443 @@
+/// Exact linear algebra over the integers and rationals: fractions that never round, Gaussian elimination
+/// for any system with its whole solution space, and integer solutions of `a x + b y = c` by the extended
+/// Euclidean algorithm, like the claw machines of 2024 day 13 need when their buttons are parallel.
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+pub type Int = i128;
+
+/// `(g, x, y)` with `a x + b y = g`, where `g` is the greatest common divisor of `a` and `b`, never
+/// negative. `ext_gcd(0, 0) == (0, 0, 0)`.
+#[must_use]
+pub fn ext_gcd(a: Int, b: Int) -> (Int, Int, Int) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// A fraction in lowest terms with a positive denominator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: Int,
+    den: Int,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    /// # Panics
+    ///
+    /// If `den` is zero.
+    #[must_use]
+    pub fn new(num: Int, den: Int) -> Self {
+        assert!(den != 0, "the denominator of a fraction cannot be zero");
+        let (g, _, _) = ext_gcd(num, den);
+        let sign = den.signum();
+        Rational {
+            num: sign * num / g,
+            den: sign * den / g,
+        }
+    }
+
+    #[must_use]
+    pub fn numerator(self) -> Int {
+        self.num
+    }
+
+    #[must_use]
+    pub fn denominator(self) -> Int {
+        self.den
+    }
+
+    #[must_use]
+    pub fn is_zero(self) -> bool {
+        self.num == 0
+    }
+
+    /// The value if it is a whole number.
+    #[must_use]
+    pub fn to_integer(self) -> Option<Int> {
+        (self.den == 1).then_some(self.num)
+    }
+}
+
+impl From<Int> for Rational {
+    fn from(value: Int) -> Self {
+        Rational { num: value, den: 1 }
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, other: Rational) -> Rational {
+        Rational::new(
+            self.num * other.den + other.num * self.den,
+            self.den * other.den,
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, other: Rational) -> Rational {
+        self + -other
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, other: Rational) -> Rational {
+        Rational::new(self.num * other.num, self.den * other.den)
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    /// # Panics
+    ///
+    /// If `other` is zero.
+    fn div(self, other: Rational) -> Rational {
+        Rational::new(self.num * other.den, self.den * other.num)
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.num * other.den).cmp(&(other.num * self.den))
+    }
+}
+
+/// Whole numbers as `3`, others as `-7/2`.
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.den {
+            1 => write!(f, "{}", self.num),
+            den => write!(f, "{}/{den}", self.num),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Every solution of a linear system.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Solutions {
+    None,
+    Unique(Vec<Rational>),
+    /// `particular` plus any combination of the `kernel` vectors.
+    Affine {
+        particular: Vec<Rational>,
+        kernel: Vec<Vec<Rational>>,
+    },
+}
+
+/// The solution of `[[a, b], [c, d]] (x, y) = (e, f)` by Cramer's rule, or `None` if the rows are
+/// dependent and there are no or infinitely many solutions.
+#[must_use]
+pub fn solve2(matrix: [[Int; 2]; 2], rhs: [Int; 2]) -> Option<[Rational; 2]> {
+    let [[a, b], [c, d]] = matrix;
+    let [e, f] = rhs;
+    let det = a * d - b * c;
+    if det == 0 {
+        return None;
+    }
+    Some([
+        Rational::new(e * d - b * f, det),
+        Rational::new(a * f - e * c, det),
+    ])
+}
+
+/// Solve `matrix x = rhs` for any number of rows and columns by Gauss-Jordan elimination.
+///
+/// # Panics
+///
+/// If the rows of `matrix` differ in length or there are not as many of them as entries in `rhs`.
+#[must_use]
+pub fn solve(matrix: &[Vec<Int>], rhs: &[Int]) -> Solutions {
+    assert_eq!(
+        matrix.len(),
+        rhs.len(),
+        "expected one right hand side per row"
+    );
+    let columns = matrix.first().map_or(0, Vec::len);
+    let mut rows: Vec<Vec<Rational>> = matrix
+        .iter()
+        .zip(rhs)
+        .map(|(row, &value)| {
+            assert_eq!(row.len(), columns, "expected rows of equal length");
+            row.iter().chain([&value]).map(|&x| x.into()).collect()
+        })
+        .collect();
+
+    // the column of the leading one in each row of the reduced form.
+    let mut pivots = vec![];
+    for column in 0..columns {
+        let r = pivots.len();
+        let Some(found) = (r..rows.len()).find(|&i| !rows[i][column].is_zero()) else {
+            continue;
+        };
+        rows.swap(r, found);
+        let pivot = rows[r][column];
+        for x in &mut rows[r] {
+            *x = *x / pivot;
+        }
+        let pivot_row = rows[r].clone();
+        for (i, row) in rows.iter_mut().enumerate() {
+            let factor = row[column];
+            if i != r && !factor.is_zero() {
+                for (x, &p) in row.iter_mut().zip(&pivot_row).skip(column) {
+                    *x = *x - factor * p;
+                }
+            }
+        }
+        pivots.push(column);
+    }
+
+    if rows[pivots.len()..]
+        .iter()
+        .any(|row| !row[columns].is_zero())
+    {
+        return Solutions::None;
+    }
+
+    let mut particular = vec![Rational::ZERO; columns];
+    for (row, &column) in rows.iter().zip(&pivots) {
+        particular[column] = row[columns];
+    }
+    let kernel: Vec<Vec<Rational>> = (0..columns)
+        .filter(|column| !pivots.contains(column))
+        .map(|free| {
+            let mut v = vec![Rational::ZERO; columns];
+            v[free] = Rational::ONE;
+            for (row, &column) in rows.iter().zip(&pivots) {
+                v[column] = -row[free];
+            }
+            v
+        })
+        .collect();
+
+    if kernel.is_empty() {
+        Solutions::Unique(particular)
+    } else {
+        Solutions::Affine { particular, kernel }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// All integer solutions of `a x + b y = c`: `(x + k dx, y + k dy)` for every integer `k`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Diophantine {
+    pub x: Int,
+    pub y: Int,
+    pub dx: Int,
+    pub dy: Int,
+}
+
+impl Diophantine {
+    /// `None` if there are no integer solutions or `a` and `b` are both zero, so the solutions are not a
+    /// line.
+    #[must_use]
+    pub fn solve(a: Int, b: Int, c: Int) -> Option<Diophantine> {
+        let (g, x, y) = ext_gcd(a, b);
+        if g == 0 || c % g != 0 {
+            return None;
+        }
+        Some(Diophantine {
+            x: x * (c / g),
+            y: y * (c / g),
+            dx: b / g,
+            dy: -a / g,
+        })
+    }
+
+    #[must_use]
+    pub fn at(&self, k: Int) -> (Int, Int) {
+        (self.x + k * self.dx, self.y + k * self.dy)
+    }
+
+    /// The solution with `x` and `y` not negative that minimises `x_cost x + y_cost y`, or `None` if there
+    /// is none or the cost has no minimum.
+    #[must_use]
+    pub fn cheapest_non_negative(&self, x_cost: Int, y_cost: Int) -> Option<(Int, Int)> {
+        // each of `start + k step >= 0` bounds `k` on one side, unless `step` is zero.
+        let (mut low, mut high) = (None, None);
+        for (start, step) in [(self.x, self.dx), (self.y, self.dy)] {
+            match step.signum() {
+                1 => low = low.max(Some(-start.div_euclid(step))),
+                -1 => {
+                    let bound = start.div_euclid(-step);
+                    high = Some(high.map_or(bound, |high: Int| high.min(bound)));
+                }
+                _ if start < 0 => return None,
+                _ => {}
+            }
+        }
+        if let (Some(low), Some(high)) = (low, high) {
+            if low > high {
+                return None;
+            }
+        }
+
+        let slope = x_cost * self.dx + y_cost * self.dy;
+        let k = match slope.signum() {
+            1 => low?,
+            -1 => high?,
+            _ => low.or(high).unwrap_or(0),
+        };
+        Some(self.at(k))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{ext_gcd, solve, solve2, Diophantine, Int, Rational, Solutions};
+
+    fn times(matrix: &[Vec<Int>], x: &[Rational]) -> Vec<Rational> {
+        matrix
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(x)
+                    .fold(Rational::ZERO, |sum, (&a, &b)| sum + Rational::from(a) * b)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn keeps_fractions_exact() {
+        let half = Rational::new(2, -4);
+        assert_eq!((half.numerator(), half.denominator()), (-1, 2));
+        assert_eq!(half + Rational::new(1, 3), Rational::new(-1, 6));
+        assert_eq!((half * Rational::from(-4)).to_integer(), Some(2));
+        assert_eq!(half / Rational::new(1, 4), Rational::from(-2));
+        assert!(half < Rational::new(-1, 3));
+        assert_eq!(Rational::new(7, 2).to_string(), "7/2");
+        assert_eq!(Rational::new(6, 3).to_string(), "2");
+    }
+
+    #[test]
+    fn finds_integer_solutions() {
+        assert_eq!(ext_gcd(240, 46), (2, -9, 47));
+        assert_eq!(ext_gcd(-6, 0), (6, -1, 0));
+
+        let line = Diophantine::solve(3, 6, 18).unwrap();
+        let (x, y) = line.at(5);
+        assert_eq!(3 * x + 6 * y, 18);
+        assert_eq!(line.cheapest_non_negative(3, 1), Some((0, 3)));
+        assert_eq!(line.cheapest_non_negative(1, 5), Some((6, 0)));
+        assert_eq!(Diophantine::solve(4, 6, 7), None);
+        assert_eq!(Diophantine::solve(0, 0, 0), None);
+        assert_eq!(
+            Diophantine::solve(0, 2, 4)
+                .unwrap()
+                .cheapest_non_negative(1, 1),
+            Some((0, 2))
+        );
+        assert_eq!(
+            Diophantine::solve(2, 3, -1)
+                .unwrap()
+                .cheapest_non_negative(1, 1),
+            None
+        );
+    }
+
+    #[test]
+    fn solves_systems() {
+        assert_eq!(
+            solve2([[94, 22], [34, 67]], [8400, 5400]),
+            Some([Rational::from(80), Rational::from(40)])
+        );
+        assert_eq!(solve2([[1, 3], [1, 3]], [9, 9]), None);
+
+        let matrix = vec![vec![2, 1, -1], vec![-3, -1, 2], vec![-2, 1, 2]];
+        assert_eq!(
+            solve(&matrix, &[8, -11, -3]),
+            Solutions::Unique(vec![2.into(), 3.into(), (-1).into()])
+        );
+
+        let dependent = vec![vec![1, 3], vec![2, 6]];
+        assert_eq!(solve(&dependent, &[1, 3]), Solutions::None);
+        let Solutions::Affine { particular, kernel } = solve(&dependent, &[9, 18]) else {
+            panic!("expected a line of solutions");
+        };
+        assert_eq!(kernel.len(), 1);
+        assert_eq!(times(&dependent, &particular), vec![9.into(), 18.into()]);
+        assert_eq!(times(&dependent, &kernel[0]), vec![Rational::ZERO; 2]);
+    }
+
+    proptest! {
+        #[test]
+        fn solutions_satisfy_the_system(
+            matrix in prop::collection::vec(prop::collection::vec(-3..4 as Int, 3), 1..4),
+            rhs in prop::collection::vec(-5..6 as Int, 3),
+        ) {
+            let rhs = &rhs[..matrix.len()];
+            let expected: Vec<Rational> = rhs.iter().map(|&x| x.into()).collect();
+            match solve(&matrix, rhs) {
+                Solutions::None => {}
+                Solutions::Unique(x) => prop_assert_eq!(times(&matrix, &x), expected),
+                Solutions::Affine { particular, kernel } => {
+                    prop_assert_eq!(times(&matrix, &particular), expected.clone());
+                    for v in kernel {
+                        prop_assert_eq!(times(&matrix, &v), vec![Rational::ZERO; matrix.len()]);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn cheapest_matches_brute_force(a in 0..6 as Int, b in 0..6 as Int, c in 0..40 as Int, x_cost in 0..4 as Int, y_cost in 0..4 as Int) {
+            let best = (0..=c)
+                .flat_map(|x| (0..=c).map(move |y| (x, y)))
+                .filter(|&(x, y)| a * x + b * y == c)
+                .map(|(x, y)| x_cost * x + y_cost * y)
+                .min();
+            let found = Diophantine::solve(a, b, c).and_then(|line| line.cheapest_non_negative(x_cost, y_cost));
+            if a > 0 || b > 0 {
+                prop_assert_eq!(found.map(|(x, y)| x_cost * x + y_cost * y), best);
+            }
+            if let Some((x, y)) = found {
+                prop_assert!(x >= 0 && y >= 0 && a * x + b * y == c);
+            }
+        }
+    }
+}
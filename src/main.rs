@@ -1,14 +1,15 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, download, read, readme, scaffold, solve, status, time,
+};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
-use advent_of_code::template::Day;
-#[cfg(feature = "today")]
-use std::process;
+use advent_of_code::template::commands::today;
 
 mod args {
-    use advent_of_code::template::Day;
-    use std::process;
+    use advent_of_code::template::{export, Day};
+    use advent_of_code::visualize::image;
+    use std::{path::PathBuf, process};
 
     pub enum AppArguments {
         Download {
@@ -16,28 +17,45 @@ mod args {
         },
         Read {
             day: Day,
+            part: Option<u8>,
+            pager: bool,
+        },
+        Readme {
+            check: bool,
         },
         Scaffold {
             day: Day,
             download: bool,
             overwrite: bool,
+            dry_run: bool,
+            template: Option<String>,
+            return_type: Option<String>,
         },
         Solve {
             day: Day,
             release: bool,
             dhat: bool,
+            profile: bool,
+            trace: bool,
+            visualize: bool,
+            export: Option<image::Options>,
             submit: Option<u8>,
         },
         All {
             release: bool,
         },
+        Status,
         Time {
             all: bool,
             day: Option<Day>,
             store: bool,
+            scale: bool,
+            export: Option<(export::Format, PathBuf)>,
         },
         #[cfg(feature = "today")]
-        Today,
+        Today {
+            wait: bool,
+        },
     }
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
@@ -50,32 +68,60 @@ mod args {
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
+                let scale = args.contains("--scale");
+
+                // exports stored timings, so a day is not expected.
+                let export = match args.opt_value_from_str("--export")? {
+                    Some(format) => Some((format, args.free_from_str()?)),
+                    None => None,
+                };
 
                 AppArguments::Time {
                     all,
-                    day: args.opt_free_from_str()?,
+                    day: if export.is_some() {
+                        None
+                    } else {
+                        args.opt_free_from_str()?
+                    },
                     store,
+                    scale,
+                    export,
                 }
             }
+            Some("status") => AppArguments::Status,
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
             },
             Some("read") => AppArguments::Read {
+                part: args.opt_value_from_str("--part")?,
+                pager: !args.contains("--no-pager"),
                 day: args.free_from_str()?,
             },
+            Some("readme") => AppArguments::Readme {
+                check: args.contains("--check"),
+            },
             Some("scaffold") => AppArguments::Scaffold {
                 day: args.free_from_str()?,
                 download: args.contains("--download"),
                 overwrite: args.contains("--overwrite"),
+                dry_run: args.contains("--dry-run"),
+                template: args.opt_value_from_str("--template")?,
+                return_type: args.opt_value_from_str("--returns")?,
             },
             Some("solve") => AppArguments::Solve {
                 day: args.free_from_str()?,
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 dhat: args.contains("--dhat"),
+                profile: args.contains("--profile"),
+                trace: args.contains("--trace"),
+                visualize: args.contains("--visualize"),
+                export: image::Options::parse(&mut args)?,
             },
             #[cfg(feature = "today")]
-            Some("today") => AppArguments::Today,
+            Some("today") => AppArguments::Today {
+                wait: args.contains("--wait"),
+            },
             Some(x) => {
                 eprintln!("Unknown command: {x}");
                 process::exit(1);
@@ -103,16 +149,33 @@ fn main() {
         }
         Ok(args) => match args {
             AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
+            AppArguments::Time {
+                day,
+                all,
+                store,
+                scale,
+                export,
+            } => time::handle(day, all, store, scale, export),
+            AppArguments::Status => status::handle(),
             AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
+            AppArguments::Read { day, part, pager } => read::handle(day, part, pager),
+            AppArguments::Readme { check } => readme::handle(check),
             AppArguments::Scaffold {
                 day,
                 download,
                 overwrite,
+                dry_run,
+                template,
+                return_type,
             } => {
-                scaffold::handle(day, overwrite);
-                if download {
+                scaffold::handle(
+                    day,
+                    overwrite,
+                    dry_run,
+                    template.as_deref(),
+                    return_type.as_deref(),
+                );
+                if download && !dry_run {
                     download::handle(day);
                 }
             }
@@ -120,25 +183,16 @@ fn main() {
                 day,
                 release,
                 dhat,
+                profile,
+                trace,
+                visualize,
+                export,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+            } => solve::handle(
+                day, release, dhat, profile, trace, visualize, export, submit,
+            ),
             #[cfg(feature = "today")]
-            AppArguments::Today => {
-                match Day::today() {
-                    Some(day) => {
-                        scaffold::handle(day, false);
-                        download::handle(day);
-                        read::handle(day)
-                    }
-                    None => {
-                        eprintln!(
-                            "`today` command can only be run between the 1st and \
-                            the 25th of december. Please use `scaffold` with a specific day."
-                        );
-                        process::exit(1)
-                    }
-                };
-            }
+            AppArguments::Today { wait } => today::handle(wait),
         },
     };
 }
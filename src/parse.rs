@@ -0,0 +1,415 @@
+/// Parsers for the common shapes of puzzle input: blank-line separated sections, lines, `key: value` pairs,
+/// numbers and grids. Every piece of input is a [`Span`] that knows where it starts, so errors point at the
+/// line and column that could not be parsed instead of panicking.
+use std::{any::type_name, fmt::Display, str::FromStr};
+
+use crate::grid::Grid;
+
+/// A parse error at a 1-based line and column of the input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Print the error of a failed parse to stderr, so a solution can bail out with `?`.
+pub fn report<T>(result: Result<T, Error>) -> Option<T> {
+    result
+        .map_err(|e| eprintln!("Failed to parse input: {e}"))
+        .ok()
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A slice of the input together with the whole input, so the position it starts at can be found when an
+/// error needs it. Taking parts of a span is cheap, however far into the input they are.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span<'a> {
+    pub text: &'a str,
+    input: &'a str,
+}
+
+impl<'a> Span<'a> {
+    #[must_use]
+    pub fn new(input: &'a str) -> Self {
+        Span { text: input, input }
+    }
+
+    /// 1-based line and column the span starts at.
+    #[must_use]
+    pub fn position(&self) -> (usize, usize) {
+        let offset = self.text.as_ptr() as usize - self.input.as_ptr() as usize;
+        let before = &self.input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        (
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+        )
+    }
+
+    /// The span of `part`, which must be a subslice of this span's text.
+    fn sub(&self, part: &'a str) -> Span<'a> {
+        debug_assert!({
+            let offset = part.as_ptr() as usize - self.text.as_ptr() as usize;
+            offset + part.len() <= self.text.len()
+        });
+        Span {
+            text: part,
+            input: self.input,
+        }
+    }
+
+    #[must_use]
+    pub fn error(&self, message: impl Into<String>) -> Error {
+        let (line, column) = self.position();
+        Error {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn trim(self) -> Self {
+        self.sub(self.text.trim())
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Fail unless the span is exactly `expected`, e.g. a fixed key.
+    pub fn expect(self, expected: &str) -> Result<(), Error> {
+        if self.text == expected {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{expected}`, found `{}`", self.text)))
+        }
+    }
+
+    /// Parse the whole (trimmed) span.
+    pub fn parse<T>(self) -> Result<T, Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let trimmed = self.trim();
+        trimmed.text.parse().map_err(|e| {
+            trimmed.error(format!(
+                "invalid {} `{}`: {e}",
+                type_name::<T>(),
+                trimmed.text
+            ))
+        })
+    }
+
+    /* ---------------------------------------------------------------------- */
+
+    pub fn lines(self) -> impl Iterator<Item = Span<'a>> {
+        self.text.lines().map(move |line| self.sub(line))
+    }
+
+    /// Groups of lines separated by blank lines. Leading and trailing blank lines are ignored.
+    #[must_use]
+    pub fn sections(self) -> Vec<Span<'a>> {
+        let mut sections = vec![];
+        let mut current: Option<(usize, usize)> = None;
+
+        for line in self.text.lines() {
+            let start = line.as_ptr() as usize - self.text.as_ptr() as usize;
+            if line.trim().is_empty() {
+                if let Some((from, to)) = current.take() {
+                    sections.push(self.sub(&self.text[from..to]));
+                }
+            } else {
+                let from = current.map_or(start, |(from, _)| from);
+                current = Some((from, start + line.len()));
+            }
+        }
+
+        if let Some((from, to)) = current {
+            sections.push(self.sub(&self.text[from..to]));
+        }
+        sections
+    }
+
+    /// Exactly `N` sections, e.g. a grid followed by a list of moves.
+    pub fn sections_exact<const N: usize>(self) -> Result<[Span<'a>; N], Error> {
+        self.exactly(self.sections(), "sections")
+    }
+
+    /// Exactly `N` lines.
+    pub fn lines_exact<const N: usize>(self) -> Result<[Span<'a>; N], Error> {
+        self.exactly(self.lines().collect(), "lines")
+    }
+
+    /// Convert `items` found in this span to an array, failing if there are not exactly `N` of them.
+    pub fn exactly<T, const N: usize>(self, items: Vec<T>, what: &str) -> Result<[T; N], Error> {
+        let found = items.len();
+        items
+            .try_into()
+            .map_err(|_| self.error(format!("expected {N} {what}, found {found}")))
+    }
+
+    pub fn split(self, separator: &'a str) -> impl Iterator<Item = Span<'a>> {
+        self.text.split(separator).map(move |part| self.sub(part))
+    }
+
+    pub fn words(self) -> impl Iterator<Item = Span<'a>> {
+        self.text.split_whitespace().map(move |word| self.sub(word))
+    }
+
+    /// Split at the first `separator`, e.g. `190: 10 19` at `:`.
+    pub fn split_once(self, separator: &str) -> Result<(Span<'a>, Span<'a>), Error> {
+        match self.text.split_once(separator) {
+            Some((left, right)) => Ok((self.sub(left), self.sub(right))),
+            None => Err(self.error(format!("expected `{separator}` in `{}`", self.text))),
+        }
+    }
+
+    /// A `key<separator>value` line, both sides trimmed.
+    pub fn key_value(self, separator: &str) -> Result<(Span<'a>, Span<'a>), Error> {
+        let (key, value) = self.split_once(separator)?;
+        Ok((key.trim(), value.trim()))
+    }
+
+    /* ---------------------------------------------------------------------- */
+
+    fn nums<T>(self, signed: bool) -> Result<Vec<T>, Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let mut nums = vec![];
+        let mut rest = self.text;
+
+        while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+            let len = rest[start..]
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len() - start);
+
+            let negative = signed && rest[..start].ends_with('-');
+            let from = if negative { start - 1 } else { start };
+
+            nums.push(self.sub(&rest[from..start + len]).parse()?);
+            rest = &rest[start + len..];
+        }
+
+        Ok(nums)
+    }
+
+    /// All unsigned numbers in the span, in order. A `-` in front of a number is ignored.
+    pub fn nums_positive<T>(self) -> Result<Vec<T>, Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.nums(false)
+    }
+
+    /// All numbers in the span, in order. A `-` directly in front of a number makes it negative.
+    pub fn nums_signed<T>(self) -> Result<Vec<T>, Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.nums(true)
+    }
+
+    /// One cell per character, ignoring whitespace. `f` returns `None` for characters that are not allowed.
+    pub fn cells<T>(self, f: impl Fn(char) -> Option<T>) -> Result<Vec<T>, Error> {
+        self.text
+            .char_indices()
+            .filter(|(_, c)| !c.is_whitespace())
+            .map(|(i, c)| {
+                f(c).ok_or_else(|| {
+                    self.sub(&self.text[i..i + c.len_utf8()])
+                        .error(format!("unexpected character `{c}`"))
+                })
+            })
+            .collect()
+    }
+
+    /// A rectangular grid with one cell per character.
+    pub fn grid<T>(self, f: impl Fn(char) -> Option<T>) -> Result<Grid<T>, Error> {
+        let rows = self
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok((line, line.cells(&f)?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        if let Some((_, first)) = rows.first() {
+            let width = first.len();
+            if let Some((line, row)) = rows.iter().find(|(_, row)| row.len() != width) {
+                return Err(line.error(format!("expected {width} columns, found {}", row.len())));
+            }
+        }
+
+        Ok(Grid::new(rows.into_iter().map(|(_, row)| row).collect()))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Parse every line of `input` with `f`.
+pub fn lines<'a, T>(
+    input: &'a str,
+    f: impl FnMut(Span<'a>) -> Result<T, Error>,
+) -> Result<Vec<T>, Error> {
+    Span::new(input).lines().map(f).collect()
+}
+
+/// Parse every section of `input` with `f`.
+pub fn sections<'a, T>(
+    input: &'a str,
+    f: impl FnMut(Span<'a>) -> Result<T, Error>,
+) -> Result<Vec<T>, Error> {
+    Span::new(input).sections().into_iter().map(f).collect()
+}
+
+/// All unsigned numbers in `s`, in order. A `-` in front of a number is ignored.
+///
+/// # Panics
+/// If a number does not fit into `T`.
+#[must_use]
+pub fn nums_positive<T>(s: &str) -> Vec<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    Span::new(s)
+        .nums_positive()
+        .unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// All numbers in `s`, in order. A `-` directly in front of a number makes it negative.
+///
+/// # Panics
+/// If a number does not fit into `T`.
+#[must_use]
+pub fn nums_signed<T>(s: &str) -> Vec<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    Span::new(s).nums_signed().unwrap_or_else(|e| panic!("{e}"))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{lines, nums_positive, nums_signed, Error, Span};
+
+    const INPUT: &str = "#.\n.#\n\nButton A: X+94, Y+34\nPrize: X=8400, Y=-5400\n";
+
+    fn error(line: usize, column: usize, message: &str) -> Error {
+        Error {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    #[test]
+    fn extracts_numbers() {
+        let line = "p=0,4 v=3,-3";
+        assert_eq!(nums_positive::<u32>(line), vec![0, 4, 3, 3]);
+        assert_eq!(nums_signed::<i32>(line), vec![0, 4, 3, -3]);
+        assert_eq!(nums_signed::<i32>("no numbers"), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn splits_sections() {
+        let [grid, machine] = Span::new(INPUT).sections_exact().unwrap();
+        assert_eq!(grid.text, "#.\n.#");
+        assert_eq!(machine.position(), (4, 1));
+        assert_eq!(
+            Span::new(INPUT).sections_exact::<3>().unwrap_err(),
+            error(1, 1, "expected 3 sections, found 2")
+        );
+
+        let [_, prize] = machine.lines_exact().unwrap();
+        let (key, value) = prize.key_value(":").unwrap();
+        key.expect("Prize").unwrap();
+        assert_eq!(value.position(), (5, 8));
+        assert_eq!(value.nums_signed::<i64>().unwrap(), vec![8400, -5400]);
+    }
+
+    #[test]
+    fn reports_positions() {
+        let [_, machine] = Span::new(INPUT).sections_exact().unwrap();
+        let [button, _]: [_; 2] = machine.lines_exact().unwrap();
+        assert_eq!(
+            button.key_value(":").unwrap().0.expect("Button B"),
+            Err(error(4, 1, "expected `Button B`, found `Button A`"))
+        );
+        assert_eq!(button.nums_positive::<u8>(), Ok(vec![94, 34]));
+        assert_eq!(
+            machine.lines().nth(1).unwrap().nums_signed::<u8>(),
+            Err(error(
+                5,
+                10,
+                "invalid u8 `8400`: number too large to fit in target type"
+            ))
+        );
+        assert_eq!(
+            Span::new("1 2\n3 x")
+                .lines()
+                .nth(1)
+                .unwrap()
+                .words()
+                .nth(1)
+                .unwrap()
+                .parse::<u32>(),
+            Err(error(
+                2,
+                3,
+                "invalid u32 `x`: invalid digit found in string"
+            ))
+        );
+        assert_eq!(lines("1\n2\n", |line| line.parse::<u32>()), Ok(vec![1, 2]));
+    }
+
+    #[test]
+    fn finds_positions_deep_in_large_inputs() {
+        let input = "1 2 3\n".repeat(100_000) + "4 x";
+        let result = lines(&input, |line| {
+            line.words()
+                .map(Span::parse::<u32>)
+                .collect::<Result<Vec<_>, _>>()
+        });
+        assert_eq!(
+            result.unwrap_err(),
+            error(100_001, 3, "invalid u32 `x`: invalid digit found in string")
+        );
+    }
+
+    #[test]
+    fn parses_grids() {
+        let [grid, _] = Span::new(INPUT).sections_exact().unwrap();
+        let grid = grid.grid(|c| (c == '#').then_some(true).or((c == '.').then_some(false)));
+        assert_eq!(grid.unwrap().0, vec![vec![true, false], vec![false, true]]);
+
+        assert_eq!(
+            Span::new("..\n.x").grid(|c| (c == '.').then_some(())),
+            Err(error(2, 2, "unexpected character `x`"))
+        );
+        assert_eq!(
+            Span::new("..\n.").grid(Some),
+            Err(error(2, 1, "expected 2 columns, found 1"))
+        );
+    }
+}
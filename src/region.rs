@@ -0,0 +1,346 @@
+/// Connected regions of equal cells in a grid, like the garden plots of 2024 day 12, measured by area,
+/// perimeter, straight sides, enclosed holes and bounding box. Sides are counted as corners, since a closed
+/// outline has as many of each.
+use std::fmt::{Display, Write};
+
+use lina::{point2, vec2, Vec2};
+
+use crate::grid::{Grid, Point, UP_RIGHT_DOWN_LEFT};
+
+/// Which neighbours of a cell belong to its region when they are equal to it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Connectivity {
+    /// The orthogonal neighbours.
+    Four,
+    /// The orthogonal and diagonal neighbours.
+    Eight,
+}
+
+const DIAGONALS: [Vec2<i32>; 4] = [vec2(1, -1), vec2(1, 1), vec2(-1, 1), vec2(-1, -1)];
+
+impl Connectivity {
+    fn directions(self) -> Vec<Vec2<i32>> {
+        match self {
+            Connectivity::Four => UP_RIGHT_DOWN_LEFT.to_vec(),
+            Connectivity::Eight => UP_RIGHT_DOWN_LEFT.into_iter().chain(DIAGONALS).collect(),
+        }
+    }
+
+    /// Cells outside a region connect the other way, so a hole cannot leak between two diagonal cells of
+    /// a region and a region cannot be split by them either.
+    fn dual(self) -> Connectivity {
+        match self {
+            Connectivity::Four => Connectivity::Eight,
+            Connectivity::Eight => Connectivity::Four,
+        }
+    }
+}
+
+/// The measurements of a region.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shape {
+    pub area: usize,
+    /// Cell edges between the region and other cells or the outside of the grid.
+    pub perimeter: usize,
+    /// Straight runs of the perimeter, inner ones included.
+    pub sides: usize,
+    /// Areas enclosed by the region that are not part of it.
+    pub holes: usize,
+    /// The corners of the bounding box, both inclusive.
+    pub min: Point,
+    pub max: Point,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Region<T> {
+    pub value: T,
+    /// Cells in the order they were found, starting with the first one row by row.
+    pub cells: Vec<Point>,
+    pub shape: Shape,
+}
+
+/// How a fence around a region is priced.
+#[derive(Clone, Copy, Debug)]
+pub struct Pricing {
+    pub name: &'static str,
+    pub price: fn(&Shape) -> usize,
+}
+
+impl Pricing {
+    pub const PERIMETER: Pricing = Pricing {
+        name: "perimeter",
+        price: |shape| shape.area * shape.perimeter,
+    };
+    pub const SIDES: Pricing = Pricing {
+        name: "sides",
+        price: |shape| shape.area * shape.sides,
+    };
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The regions of a grid, numbered in the order of their first cells row by row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Regions<T> {
+    /// The number of the region of each cell.
+    pub labels: Grid<usize>,
+    pub regions: Vec<Region<T>>,
+}
+
+impl<T: Clone + PartialEq> Regions<T> {
+    #[must_use]
+    pub fn find(grid: &Grid<T>, connectivity: Connectivity) -> Self {
+        let directions = connectivity.directions();
+        let mut labels = Grid::new_with_dimensions_uniform(grid.dimension(), usize::MAX);
+        let mut cells = vec![];
+        for start in grid.iter_coordinates() {
+            if labels[start] != usize::MAX {
+                continue;
+            }
+            let label = cells.len();
+            labels[start] = label;
+            let mut found = vec![start];
+            let mut i = 0;
+            while let Some(&p) = found.get(i) {
+                for &d in &directions {
+                    let next = p + d;
+                    if grid.get(next) == Some(&grid[start]) && labels[next] == usize::MAX {
+                        labels[next] = label;
+                        found.push(next);
+                    }
+                }
+                i += 1;
+            }
+            cells.push(found);
+        }
+
+        let regions = cells
+            .into_iter()
+            .enumerate()
+            .map(|(label, cells)| Region {
+                value: grid[cells[0]].clone(),
+                shape: measure(&labels, label, &cells, connectivity),
+                cells,
+            })
+            .collect();
+        Regions { labels, regions }
+    }
+}
+
+impl<T> Regions<T> {
+    /// The summed prices of all regions.
+    #[must_use]
+    pub fn price(&self, pricing: Pricing) -> usize {
+        self.regions
+            .iter()
+            .map(|region| (pricing.price)(&region.shape))
+            .sum()
+    }
+}
+
+impl<T: Display> Regions<T> {
+    /// One line per region with its measurements and its price under each of `pricings`, for debugging.
+    #[must_use]
+    pub fn breakdown(&self, pricings: &[Pricing]) -> String {
+        let mut out = String::new();
+        for region in &self.regions {
+            let Shape {
+                area,
+                perimeter,
+                sides,
+                holes,
+                min,
+                max,
+            } = region.shape;
+            let _ = write!(
+                out,
+                "{}: area {area}, perimeter {perimeter}, {sides} sides, {holes} holes, ({}, {})..=({}, {})",
+                region.value, min.x, min.y, max.x, max.y
+            );
+            for pricing in pricings {
+                let _ = write!(out, ", {} {}", pricing.name, (pricing.price)(&region.shape));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn measure(
+    labels: &Grid<usize>,
+    label: usize,
+    cells: &[Point],
+    connectivity: Connectivity,
+) -> Shape {
+    let inside = |p: Point| labels.get(p) == Some(&label);
+
+    let perimeter = cells
+        .iter()
+        .flat_map(|&p| UP_RIGHT_DOWN_LEFT.map(|d| p + d))
+        .filter(|&q| !inside(q))
+        .count();
+
+    // a corner turns outwards if both sides of it are outside the region, and inwards if both sides are
+    // inside but the cell between them is not.
+    let sides = cells
+        .iter()
+        .flat_map(|&p| DIAGONALS.map(|d| (p, d)))
+        .filter(|&(p, d)| {
+            let (across, down) = (inside(p + vec2(d.x, 0)), inside(p + vec2(0, d.y)));
+            (!across && !down) || (across && down && !inside(p + d))
+        })
+        .count();
+
+    let min = cells
+        .iter()
+        .fold(cells[0], |min, p| point2(min.x.min(p.x), min.y.min(p.y)));
+    let max = cells
+        .iter()
+        .fold(cells[0], |max, p| point2(max.x.max(p.x), max.y.max(p.y)));
+
+    Shape {
+        area: cells.len(),
+        perimeter,
+        sides,
+        holes: holes(min, max, inside, connectivity.dual()),
+        min,
+        max,
+    }
+}
+
+/// The number of areas outside the region that cannot reach the outside of its bounding box.
+fn holes(
+    min: Point,
+    max: Point,
+    inside: impl Fn(Point) -> bool,
+    connectivity: Connectivity,
+) -> usize {
+    // a margin of one cell around the box is always outside and connects everything that is not a hole.
+    let offset = min - point2(1, 1);
+    let size = max - min + vec2(3, 3);
+    let mut seen = Grid::new_with_dimensions(size, |p| inside(p + offset));
+    let directions = connectivity.directions();
+
+    let mut areas = 0;
+    for start in seen.iter_coordinates() {
+        if seen[start] {
+            continue;
+        }
+        areas += 1;
+        seen[start] = true;
+        let mut stack = vec![start];
+        while let Some(p) = stack.pop() {
+            for &d in &directions {
+                if seen.get(p + d) == Some(&false) {
+                    seen[p + d] = true;
+                    stack.push(p + d);
+                }
+            }
+        }
+    }
+    areas - 1
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::collections::HashSet;
+
+    use lina::point2;
+    use proptest::prelude::*;
+
+    use super::{Connectivity, Pricing, Regions};
+    use crate::grid::{Grid, UP_RIGHT_DOWN_LEFT};
+
+    fn read(input: &str) -> Grid<char> {
+        Grid::read(input, |c| c)
+    }
+
+    /// Sides as maximal runs of perimeter edges facing the same way.
+    fn count_sides(regions: &Regions<char>, label: usize) -> usize {
+        let inside = |p| regions.labels.get(p) == Some(&label);
+        let edges: HashSet<_> = regions.regions[label]
+            .cells
+            .iter()
+            .flat_map(|&p| UP_RIGHT_DOWN_LEFT.map(|d| (p, d)))
+            .filter(|&(p, d)| !inside(p + d))
+            .collect();
+        // count the edges with no neighbour on one side along the run.
+        edges
+            .iter()
+            .filter(|&&(p, d)| {
+                let along = lina::vec2(d.y, -d.x);
+                !edges.contains(&(p + along, d))
+            })
+            .count()
+    }
+
+    #[test]
+    fn measures_regions() {
+        let regions = Regions::find(&read("AAAA\nBBCD\nBBCC\nEEEC\n"), Connectivity::Four);
+        assert_eq!(regions.regions.len(), 5);
+        assert_eq!(regions.price(Pricing::PERIMETER), 140);
+        assert_eq!(regions.price(Pricing::SIDES), 80);
+
+        let c = &regions.regions[regions.labels[point2(2, 1)]];
+        assert_eq!(c.value, 'C');
+        assert_eq!((c.shape.area, c.shape.perimeter, c.shape.sides), (4, 10, 8));
+        assert_eq!((c.shape.min, c.shape.max), (point2(2, 1), point2(3, 3)));
+        assert_eq!(
+            regions.breakdown(&[Pricing::SIDES]).lines().nth(2),
+            Some("C: area 4, perimeter 10, 8 sides, 0 holes, (2, 1)..=(3, 3), sides 32")
+        );
+    }
+
+    #[test]
+    fn counts_holes() {
+        let regions = Regions::find(
+            &read("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO\n"),
+            Connectivity::Four,
+        );
+        let o = &regions.regions[0];
+        assert_eq!((o.shape.holes, o.shape.sides), (4, 20));
+        assert_eq!(regions.price(Pricing::PERIMETER), 772);
+        assert_eq!(regions.price(Pricing::SIDES), 436);
+
+        let ring = read("AAAA\nABBA\nABBA\nAAAA\n");
+        assert_eq!(
+            Regions::find(&ring, Connectivity::Four).regions[0]
+                .shape
+                .holes,
+            1
+        );
+        let cross = read("BAB\nABA\nBAB\n");
+        assert_eq!(Regions::find(&cross, Connectivity::Four).regions.len(), 9);
+        let eight = Regions::find(&cross, Connectivity::Eight);
+        // with diagonals the `B`s meet in the middle, and the `A`s between them reach the outside.
+        assert_eq!(eight.regions.len(), 2);
+        assert_eq!(eight.regions[0].shape.holes, 0);
+    }
+
+    #[test]
+    fn connects_diagonals() {
+        let regions = Regions::find(&read("AB\nBA\n"), Connectivity::Eight);
+        assert_eq!(regions.regions.len(), 2);
+        let a = regions.regions[0].shape;
+        assert_eq!((a.area, a.perimeter, a.sides, a.holes), (2, 8, 8, 0));
+
+        let diamond = Regions::find(&read(".A.\nA.A\n.A.\n"), Connectivity::Eight);
+        assert_eq!(diamond.regions[diamond.labels[point2(1, 0)]].shape.holes, 1);
+    }
+
+    proptest! {
+        #[test]
+        fn counts_sides_as_runs(cells in prop::collection::vec(prop::sample::select(vec!['A', 'B', 'C']), 36)) {
+            let rows = cells.chunks(6).map(<[char]>::to_vec).collect();
+            let regions = Regions::find(&Grid::new(rows), Connectivity::Four);
+            prop_assert_eq!(regions.regions.iter().map(|r| r.shape.area).sum::<usize>(), 36);
+            for (label, region) in regions.regions.iter().enumerate() {
+                prop_assert_eq!(region.shape.sides, count_sides(&regions, label));
+            }
+        }
+    }
+}
@@ -0,0 +1,566 @@
+/// Graph searches over a neighbour function, so any node type works: grid points, `(point, direction)`
+/// states or whole puzzle states. Nodes must be `Copy + Eq + Hash`, costs are any ordered number.
+use std::{
+    cmp::Ordering,
+    collections::{hash_map, BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// What a visitor wants the search to do after a node has been settled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Control {
+    /// Expand the node's neighbours.
+    Continue,
+    /// Don't expand the node, e.g. to limit the search depth.
+    Skip,
+    /// End the search.
+    Stop,
+}
+
+/// Costs of all reached nodes and the predecessor on one shortest path to each of them.
+#[derive(Clone, Debug)]
+pub struct Search<N, C> {
+    pub cost: HashMap<N, C>,
+    pub prev: HashMap<N, N>,
+}
+
+impl<N: Copy + Eq + Hash, C: Copy> Search<N, C> {
+    #[must_use]
+    pub fn cost(&self, node: N) -> Option<C> {
+        self.cost.get(&node).copied()
+    }
+
+    /// A shortest path from one of the starts to `to`, both included.
+    #[must_use]
+    pub fn path(&self, to: N) -> Option<Vec<N>> {
+        self.cost.get(&to)?;
+        let mut path = vec![to];
+        while let Some(&prev) = self.prev.get(path.last()?) {
+            path.push(prev);
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Breadth-first search from all `starts`, the cost of a node is its number of steps.
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(N) -> I,
+) -> Search<N, usize>
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    bfs_with(starts, neighbours, |_, _| Control::Continue)
+}
+
+/// Like [`bfs`], `visit` is called once per node in order of distance.
+pub fn bfs_with<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(N) -> I,
+    mut visit: impl FnMut(N, usize) -> Control,
+) -> Search<N, usize>
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search {
+        cost: HashMap::new(),
+        prev: HashMap::new(),
+    };
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if search.cost.insert(start, 0).is_none() {
+            queue.push_back((start, 0));
+        }
+    }
+
+    while let Some((node, steps)) = queue.pop_front() {
+        match visit(node, steps) {
+            Control::Continue => {}
+            Control::Skip => continue,
+            Control::Stop => break,
+        }
+
+        for next in neighbours(node) {
+            if let hash_map::Entry::Vacant(entry) = search.cost.entry(next) {
+                entry.insert(steps + 1);
+                search.prev.insert(next, node);
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+
+    search
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Entry of the priority queue, ordered so that [`BinaryHeap`] pops the lowest priority first.
+struct Entry<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for Entry<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Ord> Eq for Entry<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Entry<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Entry<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+/// Dijkstra's algorithm from all `starts`. `neighbours` yields `(node, edge cost)` pairs, costs must not be negative.
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(N) -> I,
+) -> Search<N, C>
+where
+    N: Copy + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    dijkstra_with(starts, |node, _| neighbours(node), |_, _| Control::Continue)
+}
+
+/// Like [`dijkstra`], `visit` is called once per node when its cost is final, and `neighbours` is also
+/// given that cost.
+pub fn dijkstra_with<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(N, C) -> I,
+    mut visit: impl FnMut(N, C) -> Control,
+) -> Search<N, C>
+where
+    N: Copy + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut search = Search {
+        cost: HashMap::new(),
+        prev: HashMap::new(),
+    };
+    let mut settled = HashSet::new();
+    let mut queue = BinaryHeap::new();
+
+    for start in starts {
+        search.cost.insert(start, C::default());
+        queue.push(Entry {
+            priority: C::default(),
+            cost: C::default(),
+            node: start,
+        });
+    }
+
+    while let Some(Entry { cost, node, .. }) = queue.pop() {
+        if !settled.insert(node) {
+            continue;
+        }
+
+        match visit(node, cost) {
+            Control::Continue => {}
+            Control::Skip => continue,
+            Control::Stop => break,
+        }
+
+        for (next, edge) in neighbours(node, cost) {
+            let next_cost = cost + edge;
+            if search.cost.get(&next).is_none_or(|&c| next_cost < c) {
+                search.cost.insert(next, next_cost);
+                search.prev.insert(next, node);
+                queue.push(Entry {
+                    priority: next_cost,
+                    cost: next_cost,
+                    node: next,
+                });
+            }
+        }
+    }
+
+    search
+}
+
+/// A* search from `start` to the first node matching `is_goal`. The `heuristic` must never overestimate
+/// the remaining cost. Nodes are expanded again when a cheaper path to them is found, so it does not need
+/// to be consistent. Returns the cost and the path, both ends included.
+pub fn astar<N, C, I>(
+    start: N,
+    mut is_goal: impl FnMut(N) -> bool,
+    mut neighbours: impl FnMut(N) -> I,
+    mut heuristic: impl FnMut(N) -> C,
+) -> Option<(C, Vec<N>)>
+where
+    N: Copy + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut search = Search {
+        cost: HashMap::from([(start, C::default())]),
+        prev: HashMap::new(),
+    };
+    let mut queue = BinaryHeap::from([Entry {
+        priority: heuristic(start),
+        cost: C::default(),
+        node: start,
+    }]);
+
+    while let Some(Entry { cost, node, .. }) = queue.pop() {
+        if is_goal(node) {
+            return Some((cost, search.path(node)?));
+        }
+        // a cheaper path to `node` was found after this entry was queued.
+        if search.cost.get(&node).is_some_and(|&c| c < cost) {
+            continue;
+        }
+
+        for (next, edge) in neighbours(node) {
+            let next_cost = cost + edge;
+            if search.cost.get(&next).is_none_or(|&c| next_cost < c) {
+                search.cost.insert(next, next_cost);
+                search.prev.insert(next, node);
+                queue.push(Entry {
+                    priority: next_cost + heuristic(next),
+                    cost: next_cost,
+                    node: next,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Breadth-first search from both ends, expanding the smaller frontier one layer at a time.
+/// `backward` yields the nodes that have an edge *to* a node, for undirected graphs it is `forward`.
+/// Returns a shortest path from `start` to `goal`, both included.
+pub fn bidirectional_bfs<N, I, J>(
+    start: N,
+    goal: N,
+    mut forward: impl FnMut(N) -> I,
+    mut backward: impl FnMut(N) -> J,
+) -> Option<Vec<N>>
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    J: IntoIterator<Item = N>,
+{
+    // steps and predecessor towards `start` / successor towards `goal` of every reached node.
+    let mut from_start: HashMap<N, (usize, Option<N>)> = HashMap::from([(start, (0, None))]);
+    let mut from_goal: HashMap<N, (usize, Option<N>)> = HashMap::from([(goal, (0, None))]);
+    let mut start_frontier = vec![start];
+    let mut goal_frontier = vec![goal];
+
+    let mut meeting = (start == goal).then_some((0, start));
+
+    while meeting.is_none() && !start_frontier.is_empty() && !goal_frontier.is_empty() {
+        let forwards = start_frontier.len() <= goal_frontier.len();
+        let (frontier, own, other) = if forwards {
+            (&mut start_frontier, &mut from_start, &from_goal)
+        } else {
+            (&mut goal_frontier, &mut from_goal, &from_start)
+        };
+
+        // the whole layer is expanded, a later meeting in it may be closer to the other end.
+        let mut next_frontier = vec![];
+        for &node in frontier.iter() {
+            let steps = own[&node].0 + 1;
+            let nexts: Vec<N> = if forwards {
+                forward(node).into_iter().collect()
+            } else {
+                backward(node).into_iter().collect()
+            };
+
+            for next in nexts {
+                if own.contains_key(&next) {
+                    continue;
+                }
+                own.insert(next, (steps, Some(node)));
+                if let Some(&(other_steps, _)) = other.get(&next) {
+                    if meeting.is_none_or(|(total, _)| steps + other_steps < total) {
+                        meeting = Some((steps + other_steps, next));
+                    }
+                }
+                next_frontier.push(next);
+            }
+        }
+        *frontier = next_frontier;
+    }
+
+    let (_, meeting) = meeting?;
+    let mut path = vec![meeting];
+    while let Some(&(_, Some(prev))) = from_start.get(path.last()?) {
+        path.push(prev);
+    }
+    path.reverse();
+    while let Some(&(_, Some(next))) = from_goal.get(path.last()?) {
+        path.push(next);
+    }
+    Some(path)
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Costs of all reached nodes and *every* predecessor on a shortest path to them,
+/// i.e. a DAG of all shortest paths from the starts.
+#[derive(Clone, Debug)]
+pub struct Predecessors<N, C> {
+    pub cost: HashMap<N, C>,
+    pub prev: HashMap<N, Vec<N>>,
+}
+
+impl<N: Copy + Eq + Hash, C: Copy + Ord> Predecessors<N, C> {
+    #[must_use]
+    pub fn cost(&self, node: N) -> Option<C> {
+        self.cost.get(&node).copied()
+    }
+
+    /// All nodes that lie on a shortest path to any of `ends`, the ends included.
+    #[must_use]
+    pub fn nodes_on_paths(&self, ends: impl IntoIterator<Item = N>) -> HashSet<N> {
+        let mut stack: Vec<N> = ends
+            .into_iter()
+            .filter(|e| self.cost.contains_key(e))
+            .collect();
+        let mut seen: HashSet<N> = stack.iter().copied().collect();
+
+        while let Some(node) = stack.pop() {
+            for &prev in self.prev.get(&node).into_iter().flatten() {
+                if seen.insert(prev) {
+                    stack.push(prev);
+                }
+            }
+        }
+
+        seen
+    }
+
+    /// Number of distinct shortest paths from the starts to each reached node.
+    #[must_use]
+    pub fn path_counts(&self) -> HashMap<N, u64> {
+        let mut nodes: Vec<(C, N)> = self.cost.iter().map(|(&n, &c)| (c, n)).collect();
+        // edge costs are positive, so predecessors always have a lower cost.
+        nodes.sort_unstable_by_key(|&(cost, _)| cost);
+
+        let mut counts: HashMap<N, u64> = HashMap::new();
+        for (_, node) in nodes {
+            let count = match self.prev.get(&node) {
+                Some(prev) if !prev.is_empty() => prev.iter().map(|p| counts[p]).sum(),
+                _ => 1,
+            };
+            counts.insert(node, count);
+        }
+
+        counts
+    }
+}
+
+/// Dijkstra's algorithm that keeps every predecessor on a shortest path. Edge costs must be positive.
+pub fn all_shortest_paths<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(N) -> I,
+) -> Predecessors<N, C>
+where
+    N: Copy + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut dag = Predecessors {
+        cost: HashMap::new(),
+        prev: HashMap::new(),
+    };
+    let mut settled = HashSet::new();
+    let mut queue = BinaryHeap::new();
+
+    for start in starts {
+        dag.cost.insert(start, C::default());
+        queue.push(Entry {
+            priority: C::default(),
+            cost: C::default(),
+            node: start,
+        });
+    }
+
+    while let Some(Entry { cost, node, .. }) = queue.pop() {
+        if !settled.insert(node) {
+            continue;
+        }
+
+        for (next, edge) in neighbours(node) {
+            let next_cost = cost + edge;
+            match dag.cost.get(&next).map(|c| next_cost.cmp(c)) {
+                Some(Ordering::Greater) => {}
+                Some(Ordering::Equal) => dag.prev.entry(next).or_default().push(node),
+                None | Some(Ordering::Less) => {
+                    dag.cost.insert(next, next_cost);
+                    dag.prev.insert(next, vec![node]);
+                    queue.push(Entry {
+                        priority: next_cost,
+                        cost: next_cost,
+                        node: next,
+                    });
+                }
+            }
+        }
+    }
+
+    dag
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        all_shortest_paths, astar, bfs, bfs_with, bidirectional_bfs, dijkstra, dijkstra_with,
+        Control,
+    };
+
+    /// A diamond with a slow and a fast side: 0 -> 1 -> 3 (cost 1 + 1) and 0 -> 2 -> 3 (cost 5 + 1), then 3 -> 4.
+    fn weighted(node: u8) -> Vec<(u8, u32)> {
+        match node {
+            0 => vec![(1, 1), (2, 5)],
+            1 | 2 => vec![(3, 1)],
+            3 => vec![(4, 1)],
+            _ => vec![],
+        }
+    }
+
+    fn unweighted(node: u8) -> Vec<u8> {
+        weighted(node).into_iter().map(|(n, _)| n).collect()
+    }
+
+    /// A line graph 0 - 1 - ... - 9.
+    fn line(node: i32) -> Vec<i32> {
+        [node - 1, node + 1]
+            .into_iter()
+            .filter(|n| (0..10).contains(n))
+            .collect()
+    }
+
+    #[test]
+    fn searches_breadth_first() {
+        let search = bfs([0], unweighted);
+        assert_eq!(search.cost(3), Some(2));
+        assert_eq!(search.cost(4), Some(3));
+        assert_eq!(search.path(4), Some(vec![0, 1, 3, 4]));
+        assert_eq!(search.cost(5), None);
+    }
+
+    #[test]
+    fn limits_depth_with_visitor() {
+        let mut visited = vec![];
+        let search = bfs_with([5], line, |node, steps| {
+            visited.push(node);
+            if steps == 2 {
+                Control::Skip
+            } else {
+                Control::Continue
+            }
+        });
+        assert_eq!(search.cost.len(), 5);
+        assert_eq!(visited, vec![5, 4, 6, 3, 7]);
+
+        let search = bfs_with([0], line, |node, _| {
+            if node == 3 {
+                Control::Stop
+            } else {
+                Control::Continue
+            }
+        });
+        assert_eq!(search.cost(3), Some(3));
+        assert_eq!(search.cost(4), None);
+    }
+
+    #[test]
+    fn finds_cheapest_paths() {
+        let search = dijkstra([0], weighted);
+        assert_eq!(search.cost(4), Some(3));
+        assert_eq!(search.path(4), Some(vec![0, 1, 3, 4]));
+
+        let mut order = vec![];
+        let mut costs = vec![];
+        dijkstra_with(
+            [0],
+            |node, cost| {
+                costs.push((node, cost));
+                weighted(node)
+            },
+            |node, cost| {
+                order.push((node, cost));
+                Control::Continue
+            },
+        );
+        assert_eq!(order, vec![(0, 0), (1, 1), (3, 2), (4, 3), (2, 5)]);
+        assert_eq!(costs, order);
+    }
+
+    #[test]
+    fn searches_with_heuristic() {
+        let goal = 9;
+        let result = astar(
+            0,
+            |n| n == goal,
+            |n| line(n).into_iter().map(|n| (n, 1)),
+            |n: i32| (goal - n).abs(),
+        );
+        assert_eq!(result, Some((9, (0..=9).collect())));
+        assert_eq!(astar(0, |n| n == 42, weighted, |_| 0), None);
+
+        // admissible but not consistent: `2` is first reached the expensive way.
+        let edges = |n| match n {
+            0 => vec![(1, 1), (2, 3)],
+            1 => vec![(2, 1)],
+            2 => vec![(3, 3)],
+            _ => vec![],
+        };
+        let heuristic = |n| if n == 1 { 4 } else { 0 };
+        assert_eq!(
+            astar(0, |n| n == 3, edges, heuristic),
+            Some((5, vec![0, 1, 2, 3]))
+        );
+    }
+
+    #[test]
+    fn searches_from_both_ends() {
+        assert_eq!(
+            bidirectional_bfs(2, 7, line, line),
+            Some(vec![2, 3, 4, 5, 6, 7])
+        );
+        assert_eq!(bidirectional_bfs(4, 4, line, line), Some(vec![4]));
+        assert_eq!(bidirectional_bfs(0, 9, unweighted, |_| vec![]), None);
+    }
+
+    #[test]
+    fn keeps_all_shortest_paths() {
+        // two paths of equal cost: 0 -> 1 -> 3 and 0 -> 2 -> 3.
+        let dag = all_shortest_paths([0], |node: u8| match node {
+            0 => vec![(1, 1), (2, 1)],
+            1 | 2 => vec![(3, 1)],
+            3 => vec![(4, 5)],
+            _ => vec![],
+        });
+        assert_eq!(dag.cost(4), Some(7));
+        assert_eq!(dag.nodes_on_paths([3]).len(), 4);
+        assert_eq!(dag.nodes_on_paths([1]).len(), 2);
+        assert_eq!(dag.path_counts()[&4], 2);
+    }
+}
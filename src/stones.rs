@@ -0,0 +1,350 @@
+/// Stones of 2024 day 11 that split or grow every blink. Values and counts are arbitrary precision, so any
+/// number of blinks can be counted exactly. The values reachable from a few stones form a small closed set,
+/// and counts for huge numbers of blinks come from powers of its transition matrix modulo a prime.
+use std::collections::HashMap;
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+use crate::parse::{self, Span};
+
+/// A prime to count modulo when the count is too large to write down.
+pub const PRIME: u64 = 1_000_000_007;
+
+/// The stones a stone turns into after one blink: `0` becomes `1`, an even number of digits splits in
+/// half and anything else is multiplied by 2024.
+#[must_use]
+pub fn blink(stone: &BigUint) -> (BigUint, Option<BigUint>) {
+    if stone.is_zero() {
+        return (BigUint::one(), None);
+    }
+    let digits = stone.to_str_radix(10);
+    if digits.len().is_multiple_of(2) {
+        let (left, right) = digits.split_at(digits.len() / 2);
+        let half = |s: &str| s.parse().expect("expected only digits");
+        (half(left), Some(half(right)))
+    } else {
+        (stone * 2024u32, None)
+    }
+}
+
+/// How many stones there are of each value.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stones(pub HashMap<BigUint, BigUint>);
+
+impl Stones {
+    pub fn parse(input: &str) -> Result<Stones, parse::Error> {
+        let mut stones = Stones::default();
+        for word in Span::new(input).words() {
+            *stones.0.entry(word.parse()?).or_default() += 1u32;
+        }
+        Ok(stones)
+    }
+
+    #[must_use]
+    pub fn blink(&self) -> Stones {
+        let mut next = Stones::default();
+        for (stone, count) in &self.0 {
+            let (left, right) = blink(stone);
+            *next.0.entry(left).or_default() += count;
+            if let Some(right) = right {
+                *next.0.entry(right).or_default() += count;
+            }
+        }
+        next
+    }
+
+    /// The total number of stones.
+    #[must_use]
+    pub fn count(&self) -> BigUint {
+        self.0.values().sum()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Every value the stones will ever have, numbered, with the values each one becomes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Closure {
+    pub values: Vec<BigUint>,
+    next: Vec<(usize, Option<usize>)>,
+    /// How many stones of each value there are before blinking.
+    start: Vec<BigUint>,
+}
+
+impl Closure {
+    /// Find the values reachable from `stones`, or `None` if there are more than `limit`.
+    #[must_use]
+    pub fn find(stones: &Stones, limit: usize) -> Option<Closure> {
+        let mut index: HashMap<BigUint, usize> = HashMap::new();
+        let mut values = vec![];
+        let mut number = |value: BigUint, values: &mut Vec<BigUint>| {
+            *index.entry(value.clone()).or_insert_with(|| {
+                values.push(value);
+                values.len() - 1
+            })
+        };
+
+        let starts: Vec<(usize, &BigUint)> = stones
+            .0
+            .iter()
+            .map(|(stone, count)| (number(stone.clone(), &mut values), count))
+            .collect();
+        let mut next = vec![];
+        while next.len() < values.len() {
+            if values.len() > limit {
+                return None;
+            }
+            let (left, right) = blink(&values[next.len()]);
+            let left = number(left, &mut values);
+            let right = right.map(|right| number(right, &mut values));
+            next.push((left, right));
+        }
+
+        let mut start = vec![BigUint::zero(); values.len()];
+        for (i, count) in starts {
+            start[i] += count;
+        }
+        Some(Closure {
+            values,
+            next,
+            start,
+        })
+    }
+
+    /// One blink of counts per value, with any counting type.
+    fn step<T: Clone + Zero>(&self, counts: &[T], add: impl Fn(&mut T, &T)) -> Vec<T> {
+        let mut next = vec![T::zero(); counts.len()];
+        for (i, count) in counts.iter().enumerate() {
+            if count.is_zero() {
+                continue;
+            }
+            let (left, right) = self.next[i];
+            add(&mut next[left], count);
+            if let Some(right) = right {
+                add(&mut next[right], count);
+            }
+        }
+        next
+    }
+
+    /// The exact number of stones after `blinks` blinks.
+    #[must_use]
+    pub fn count(&self, blinks: usize) -> BigUint {
+        let mut counts = self.start.clone();
+        for _ in 0..blinks {
+            counts = self.step(&counts, |sum, count| *sum += count);
+        }
+        counts.iter().sum()
+    }
+
+    /// The number of stones after each of the first `len` blinks, modulo `modulus`.
+    fn counts_mod(&self, len: usize, modulus: u64) -> Vec<u64> {
+        let add = |sum: &mut u64, count: &u64| *sum = (*sum + count) % modulus;
+        let mut counts: Vec<u64> = self
+            .start
+            .iter()
+            .map(|count| (count % modulus).try_into().unwrap())
+            .collect();
+        let mut totals = vec![];
+        for _ in 0..len {
+            totals.push(counts.iter().fold(0, |sum, count| (sum + count) % modulus));
+            counts = self.step(&counts, add);
+        }
+        totals
+    }
+
+    /// The number of stones after `blinks` blinks modulo the prime `modulus`, for any number of blinks.
+    ///
+    /// Counts are the transition matrix `M` of the closure applied to the starting counts. By Cayley-Hamilton
+    /// `M^k` is a polynomial in `M` of degree below the number of values, so the totals follow a linear
+    /// recurrence that is found from the first two totals per value. `M^k` then reduces to `x^k` modulo the
+    /// recurrence, found by repeated squaring, which only needs the first totals again.
+    ///
+    /// # Panics
+    ///
+    /// If `modulus` does not fit in 32 bits.
+    #[must_use]
+    pub fn count_mod(&self, blinks: u64, modulus: u64) -> u64 {
+        assert!(modulus < 1 << 32, "the modulus must fit in 32 bits");
+        let totals = self.counts_mod(2 * self.values.len() + 1, modulus);
+        if let Some(&total) = totals.get(blinks as usize) {
+            return total;
+        }
+        let recurrence = recurrence(&totals, modulus);
+        power(blinks, &recurrence, modulus)
+            .iter()
+            .zip(&totals)
+            .fold(0, |sum, (a, b)| (sum + a * b) % modulus)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The shortest `c` with `s[t] = c[0] s[t - 1] + c[1] s[t - 2] + ...` for all `t` from `c.len()` on, modulo
+/// a prime, by Berlekamp-Massey.
+fn recurrence(sequence: &[u64], p: u64) -> Vec<u64> {
+    let inverse = |a: u64| {
+        let (mut result, mut base, mut exponent) = (1, a, p - 2);
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result * base % p;
+            }
+            base = base * base % p;
+            exponent >>= 1;
+        }
+        result
+    };
+
+    // connection polynomials `1 - c[0] x - c[1] x^2 - ...`, current and before the last length change.
+    let (mut current, mut previous) = (vec![1], vec![1]);
+    let (mut len, mut shift, mut last) = (0, 1, 1);
+    for t in 0..sequence.len() {
+        let discrepancy = (0..=len).fold(0, |sum, i| (sum + current[i] * sequence[t - i]) % p);
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+        let factor = discrepancy * inverse(last) % p;
+        let old = current.clone();
+        current.resize(current.len().max(previous.len() + shift), 0);
+        for (i, &b) in previous.iter().enumerate() {
+            current[i + shift] = (current[i + shift] + p - factor * b % p) % p;
+        }
+        if 2 * len <= t {
+            (len, previous, last, shift) = (t + 1 - len, old, discrepancy, 1);
+        } else {
+            shift += 1;
+        }
+    }
+    current.resize(len + 1, 0);
+    current[1..].iter().map(|&c| (p - c) % p).collect()
+}
+
+/// `x^exponent` modulo `x^d - c[0] x^(d - 1) - ... - c[d - 1]`, as coefficients of `1, x, ..., x^(d - 1)`.
+fn power(exponent: u64, recurrence: &[u64], p: u64) -> Vec<u64> {
+    let d = recurrence.len();
+    let multiply = |a: &[u64], b: &[u64]| -> Vec<u64> {
+        // products are below `2^64`, so sums of them fit easily.
+        let mut product = vec![0u128; (2 * d).saturating_sub(1)];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                product[i + j] += u128::from(x * y);
+            }
+        }
+        for i in (d..product.len()).rev() {
+            let top = (product[i] % u128::from(p)) as u64;
+            for (j, &c) in recurrence.iter().enumerate() {
+                product[i - 1 - j] += u128::from(top * c);
+            }
+        }
+        product.truncate(d);
+        product
+            .iter()
+            .map(|&x| (x % u128::from(p)) as u64)
+            .collect()
+    };
+
+    let mut result = vec![0; d];
+    let mut base = vec![0; d];
+    if d == 0 {
+        return result;
+    }
+    result[0] = 1;
+    if d == 1 {
+        base[0] = recurrence[0];
+    } else {
+        base[1] = 1;
+    }
+    let mut exponent = exponent;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = multiply(&result, &base);
+        }
+        base = multiply(&base, &base);
+        exponent >>= 1;
+    }
+    result
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use num_bigint::BigUint;
+    use proptest::prelude::*;
+
+    use super::{blink, power, recurrence, Closure, Stones, PRIME};
+
+    fn count_exactly(stones: &Stones, blinks: usize) -> BigUint {
+        (0..blinks)
+            .fold(stones.clone(), |stones, _| stones.blink())
+            .count()
+    }
+
+    #[test]
+    fn blinks_big_values() {
+        let big: BigUint = "18446744073709551616".parse().unwrap();
+        assert_eq!(
+            blink(&big),
+            (
+                BigUint::from(1_844_674_407u32),
+                Some(3_709_551_616u32.into())
+            )
+        );
+        let odd: BigUint = "184467440737095516161".parse().unwrap();
+        assert_eq!(blink(&odd).0, odd.clone() * 2024u32);
+        assert_eq!(blink(&1000u32.into()), (10u32.into(), Some(0u32.into())));
+        assert_eq!(blink(&0u32.into()), (1u32.into(), None));
+    }
+
+    #[test]
+    fn counts_the_example() {
+        let stones = Stones::parse("125 17\n").unwrap();
+        assert_eq!(count_exactly(&stones, 6), 22u32.into());
+        let closure = Closure::find(&stones, 10_000).unwrap();
+        assert_eq!(closure.count(25), 55312u32.into());
+        assert_eq!(closure.count(75), 65_601_038_650_482u64.into());
+        assert_eq!(closure.count_mod(75, PRIME), 65_601_038_650_482 % PRIME);
+        assert_eq!(Stones::parse("1 x").unwrap_err().column, 3);
+    }
+
+    #[test]
+    fn counts_many_blinks() {
+        let stones = Stones::parse("0").unwrap();
+        let closure = Closure::find(&stones, 100).unwrap();
+        let exact = closure.count(10_000);
+        assert!(exact.to_string().len() > 1000);
+        assert_eq!(
+            closure.count_mod(10_000, PRIME),
+            u64::try_from(exact % PRIME).unwrap()
+        );
+        assert!(Closure::find(&stones, 10).is_none());
+    }
+
+    #[test]
+    fn finds_recurrences() {
+        let fibonacci = [0, 1, 1, 2, 3, 5, 8, 13, 21];
+        assert_eq!(recurrence(&fibonacci, PRIME), vec![1, 1]);
+        assert_eq!(recurrence(&[3, 6, 12, 24], PRIME), vec![2]);
+        assert!(recurrence(&[0, 0, 0], PRIME).is_empty());
+
+        let f = |n| power(n, &[1, 1], PRIME)[1];
+        assert_eq!(f(10), 55);
+        assert_eq!(f(1000), 517_691_607);
+    }
+
+    proptest! {
+        #[test]
+        fn matches_exact_counts(stones in prop::collection::vec(0..100u32, 1..4), blinks in 0..500u64) {
+            let input: Vec<String> = stones.iter().map(u32::to_string).collect();
+            let stones = Stones::parse(&input.join(" ")).unwrap();
+            let closure = Closure::find(&stones, 1000).unwrap();
+            let exact = closure.count(blinks as usize);
+            if blinks < 30 {
+                prop_assert_eq!(count_exactly(&stones, blinks as usize), exact.clone());
+            }
+            prop_assert_eq!(closure.count_mod(blinks, PRIME), u64::try_from(exact % PRIME).unwrap());
+        }
+    }
+}
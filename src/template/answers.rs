@@ -0,0 +1,214 @@
+use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use tinyjson::JsonValue;
+
+use crate::template::Day;
+
+static ANSWERS_FILE_PATH: &str = "./data/answers.json";
+
+/// Represents the accepted answers for a single day.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Answer {
+    pub day: Day,
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+impl Answer {
+    /// Number of stars earned for the day, i.e. the number of accepted parts.
+    pub fn stars(&self) -> u8 {
+        u8::from(self.part_1.is_some()) + u8::from(self.part_2.is_some())
+    }
+}
+
+/// Represents the accepted answers for a set of days.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct Answers {
+    pub data: Vec<Answer>,
+}
+
+impl Answers {
+    /// Dehydrate answers to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(ANSWERS_FILE_PATH)?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate answers from a JSON file. If not present, returns empty answers.
+    pub fn read_from_file() -> Self {
+        fs::read_to_string(ANSWERS_FILE_PATH)
+            .map_err(|x| x.to_string())
+            .and_then(Answers::try_from)
+            .unwrap_or_default()
+    }
+
+    /// Record an accepted answer for one part of a day, overwriting a previous answer if present.
+    #[must_use]
+    pub fn record(&self, day: Day, part: u8, answer: &str) -> Self {
+        let mut data = self.data.clone();
+
+        let index = data.iter().position(|a| a.day == day).unwrap_or_else(|| {
+            data.push(Answer {
+                day,
+                part_1: None,
+                part_2: None,
+            });
+            data.len() - 1
+        });
+
+        match part {
+            1 => data[index].part_1 = Some(answer.into()),
+            2 => data[index].part_2 = Some(answer.into()),
+            _ => {}
+        }
+
+        data.sort_unstable_by_key(|a| a.day);
+        Answers { data }
+    }
+
+    pub fn get(&self, day: Day) -> Option<&Answer> {
+        self.data.iter().find(|a| a.day == day)
+    }
+
+    /// Total number of stars earned.
+    pub fn stars(&self) -> u32 {
+        self.data.iter().map(|a| u32::from(a.stars())).sum()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Answers> for JsonValue {
+    fn from(value: Answers) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Answers {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Answers {
+            data: json_data
+                .iter()
+                .map(Answer::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Answer> for JsonValue {
+    fn from(value: &Answer) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+
+        for (key, part) in [("part_1", &value.part_1), ("part_2", &value.part_2)] {
+            map.insert(
+                key.into(),
+                part.clone().map_or(JsonValue::Null, JsonValue::String),
+            );
+        }
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Answer {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected answer to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected answer.day to be a Day struct.")?;
+
+        let part_1 = json
+            .get("part_1")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected answer.part_1 to be null or string.")?;
+
+        let part_2 = json
+            .get("part_2")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected answer.part_2 to be null or string.")?;
+
+        Ok(Answer {
+            day,
+            part_1: part_1.cloned(),
+            part_2: part_2.cloned(),
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Answer, Answers};
+    use crate::day;
+
+    #[test]
+    fn round_trips_json() {
+        let answers = Answers::default()
+            .record(day!(3), 1, "161")
+            .record(day!(1), 2, "31");
+        let json = tinyjson::JsonValue::from(answers.clone())
+            .stringify()
+            .unwrap();
+        let parsed = Answers::try_from(json).unwrap();
+        assert_eq!(parsed.data, answers.data);
+    }
+
+    #[test]
+    fn records_answers() {
+        let answers = Answers::default()
+            .record(day!(2), 1, "10")
+            .record(day!(1), 1, "5")
+            .record(day!(2), 2, "20");
+
+        assert_eq!(answers.data.len(), 2);
+        assert_eq!(answers.data[0].day, day!(1));
+        assert_eq!(
+            answers.get(day!(2)),
+            Some(&Answer {
+                day: day!(2),
+                part_1: Some("10".into()),
+                part_2: Some("20".into()),
+            })
+        );
+        assert_eq!(answers.stars(), 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_malformed_answers() {
+        let json = r#"{ "data": [{ "day": "26", "part_1": null, "part_2": null }] }"#.to_string();
+        Answers::try_from(json).unwrap();
+    }
+}
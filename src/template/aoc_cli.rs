@@ -46,7 +46,8 @@ pub fn read(day: Day) -> Result<Output, AocCommandError> {
         day,
     );
 
-    call_aoc_cli(&args)
+    // the description is rendered from the puzzle file instead.
+    call_aoc_cli_captured(&args)
 }
 
 pub fn download(day: Day) -> Result<Output, AocCommandError> {
@@ -67,8 +68,8 @@ pub fn download(day: Day) -> Result<Output, AocCommandError> {
 
     let output = call_aoc_cli(&args)?;
     println!("---");
-    println!("🎄 Successfully wrote input to \"{}\".", &input_path);
-    println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
+    println!("🎄 Successfully wrote input to \"{}\".", input_path);
+    println!("🎄 Successfully wrote puzzle to \"{}\".", puzzle_path);
     Ok(output)
 }
 
@@ -77,18 +78,27 @@ pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandErro
     let mut args = build_args("submit", &[], day);
     args.push(part.to_string());
     args.push(result.to_string());
-    call_aoc_cli(&args)
+
+    // the response is captured so callers can check whether the answer was accepted.
+    let output = call_aoc_cli_captured(&args)?;
+    print!("{}", String::from_utf8_lossy(&output.stdout));
+    Ok(output)
+}
+
+/// Whether the captured output of [`submit`] reports an accepted answer.
+pub fn is_correct_answer(output: &Output) -> bool {
+    String::from_utf8_lossy(&output.stdout).contains("That's the right answer")
 }
 
 fn get_input_path(day: Day) -> String {
     format!("data/inputs/{day}.txt")
 }
 
-fn get_puzzle_path(day: Day) -> String {
+pub fn get_puzzle_path(day: Day) -> String {
     format!("data/puzzles/{day}.md")
 }
 
-fn get_year() -> Option<u16> {
+pub fn get_year() -> Option<u16> {
     match std::env::var("AOC_YEAR") {
         Ok(x) => x.parse().ok().or(None),
         Err(_) => None,
@@ -123,3 +133,18 @@ fn call_aoc_cli(args: &[String]) -> Result<Output, AocCommandError> {
         Err(AocCommandError::BadExitStatus(output))
     }
 }
+
+fn call_aoc_cli_captured(args: &[String]) -> Result<Output, AocCommandError> {
+    let output = Command::new("aoc")
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|_| AocCommandError::CommandNotCallable)?;
+
+    if output.status.success() {
+        Ok(output)
+    } else {
+        Err(AocCommandError::BadExitStatus(output))
+    }
+}
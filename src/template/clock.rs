@@ -0,0 +1,137 @@
+/// Calendar helpers for puzzle unlock times, without pulling in a date library.
+/// All timestamps are seconds since the unix epoch.
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Puzzles unlock at midnight on the Advent of Code server, which runs on UTC-5.
+pub const SERVER_UTC_OFFSET: i32 = -5;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[must_use]
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Current year in UTC.
+#[must_use]
+pub fn current_year() -> u16 {
+    civil_from_days(now() / SECONDS_PER_DAY).0
+}
+
+/// Time at which the puzzle for `day` of december `year` unlocks.
+#[must_use]
+pub fn unlock_time(year: u16, day: u8) -> u64 {
+    let midnight = days_from_civil(year, 12, day) * SECONDS_PER_DAY;
+    midnight.saturating_add_signed(-i64::from(SERVER_UTC_OFFSET) * 3600)
+}
+
+/// The next puzzle to unlock after `now` as `(year, day, unlock_time)`.
+/// With a `year`, only puzzles of that year are considered.
+#[must_use]
+pub fn next_unlock(now: u64, year: Option<u16>) -> Option<(u16, u8, u64)> {
+    let years = match year {
+        Some(year) => year..=year,
+        None => {
+            let year = civil_from_days(now / SECONDS_PER_DAY).0;
+            year..=year + 1
+        }
+    };
+
+    years
+        .flat_map(|year| (1..=25).map(move |day| (year, day, unlock_time(year, day))))
+        .find(|&(_, _, unlock)| unlock > now)
+}
+
+/// Format a span of seconds as its two most significant units, e.g. `2d 4h`, `1h 12m` or `42s`.
+#[must_use]
+pub fn format_span(secs: u64) -> String {
+    let units = [
+        (secs / SECONDS_PER_DAY, "d"),
+        (secs / 3600 % 24, "h"),
+        (secs / 60 % 60, "m"),
+        (secs % 60, "s"),
+    ];
+
+    let first = units.iter().position(|(x, _)| *x > 0).unwrap_or(3);
+
+    units[first..]
+        .iter()
+        .take(2)
+        .map(|(x, unit)| format!("{x}{unit}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Days since 1970-01-01 of a civil date, see: http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: u16, month: u8, day: u8) -> u64 {
+    let (month, day) = (u64::from(month), u64::from(day));
+    let year = u64::from(year) - u64::from(month <= 2);
+    let era = year / 400;
+    let yoe = year - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Civil date (year, month, day) of a day count since 1970-01-01, see: http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+#[allow(clippy::cast_possible_truncation)]
+fn civil_from_days(days: u64) -> (u16, u8, u8) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year as u16, month as u8, day as u8)
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{civil_from_days, days_from_civil, format_span, next_unlock, unlock_time};
+
+    #[test]
+    fn converts_dates() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2024, 12, 1), 20058);
+        assert_eq!(civil_from_days(20058), (2024, 12, 1));
+        assert_eq!(civil_from_days(20088), (2024, 12, 31));
+        assert_eq!(civil_from_days(20089), (2025, 1, 1));
+    }
+
+    #[test]
+    fn computes_unlock_times() {
+        // 2024-12-01T05:00:00Z
+        assert_eq!(unlock_time(2024, 1), 1_733_029_200);
+        assert_eq!(unlock_time(2024, 2) - unlock_time(2024, 1), 86_400);
+    }
+
+    #[test]
+    fn finds_next_unlock() {
+        let first = unlock_time(2024, 1);
+        assert_eq!(next_unlock(first - 1, None), Some((2024, 1, first)));
+        assert_eq!(
+            next_unlock(first, None),
+            Some((2024, 2, unlock_time(2024, 2)))
+        );
+        // after the 25th, the next puzzle is in the following year.
+        let last = unlock_time(2024, 25);
+        assert_eq!(
+            next_unlock(last, None),
+            Some((2025, 1, unlock_time(2025, 1)))
+        );
+        assert_eq!(next_unlock(last, Some(2024)), None);
+    }
+
+    #[test]
+    fn formats_spans() {
+        assert_eq!(format_span(0), "0s");
+        assert_eq!(format_span(42), "42s");
+        assert_eq!(format_span(3600 + 12 * 60 + 5), "1h 12m");
+        assert_eq!(format_span(2 * 86_400 + 4 * 3600), "2d 4h");
+    }
+}
@@ -1,3 +1,4 @@
+use crate::template::meta::Meta;
 use crate::template::{aoc_cli, Day};
 use std::process;
 
@@ -11,4 +12,9 @@ pub fn handle(day: Day) {
         eprintln!("failed to call aoc-cli: {e}");
         process::exit(1);
     };
+
+    // picks up the title of the downloaded puzzle.
+    if let Err(e) = Meta::read_from_file(day).store_file() {
+        eprintln!("Failed to store puzzle metadata: {e}");
+    }
 }
@@ -1,8 +1,71 @@
-use std::process;
+use std::{
+    env, fs,
+    io::{IsTerminal, Write},
+    process::{self, Command, Stdio},
+};
 
-use crate::template::{aoc_cli, Day};
+use terminal_size::{Height, Width};
 
-pub fn handle(day: Day) {
+use crate::template::{aoc_cli, puzzle, Day};
+
+const DEFAULT_WIDTH: usize = 80;
+const DEFAULT_HEIGHT: usize = 24;
+
+fn env_size(name: &str, default: usize) -> usize {
+    env::var(name)
+        .ok()
+        .and_then(|x| x.parse().ok())
+        .filter(|&x| x > 0)
+        .unwrap_or(default)
+}
+
+/// The width and height of the terminal. Shells do not export `COLUMNS` and `LINES`, so they are only
+/// read if stdout is not a terminal.
+fn terminal_dimensions() -> (usize, usize) {
+    match terminal_size::terminal_size() {
+        Some((Width(width), Height(height))) if width > 0 && height > 0 => {
+            (width.into(), height.into())
+        }
+        _ => (
+            env_size("COLUMNS", DEFAULT_WIDTH),
+            env_size("LINES", DEFAULT_HEIGHT),
+        ),
+    }
+}
+
+/// Print `text`, through `$PAGER` (`less -R` by default) if it does not fit the terminal.
+fn page(text: &str) {
+    let fits = text.lines().count() < terminal_dimensions().1;
+    if fits || !std::io::stdout().is_terminal() {
+        println!("{text}");
+        return;
+    }
+
+    let pager = env::var("PAGER").unwrap_or_else(|_| "less -R".into());
+    let mut args = pager.split_whitespace();
+
+    let child = args.next().and_then(|program| {
+        Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .spawn()
+            .ok()
+    });
+
+    match child {
+        Some(mut child) => {
+            if let Some(mut stdin) = child.stdin.take() {
+                // the pager may be quit before everything has been written.
+                let _ = writeln!(stdin, "{text}");
+            }
+            let _ = child.wait();
+        }
+        None => println!("{text}"),
+    }
+}
+
+/// Fetch the puzzle description with aoc-cli, which stores it to `data/puzzles`.
+fn fetch(day: Day) {
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
@@ -13,3 +76,38 @@ pub fn handle(day: Day) {
         process::exit(1);
     };
 }
+
+/// Render the stored puzzle description of `day`. It is only fetched if it has not been stored yet,
+/// or if part two is requested but was not unlocked when it was stored.
+pub fn handle(day: Day, part: Option<u8>, pager: bool) {
+    if part.is_some_and(|p| !matches!(p, 1 | 2)) {
+        eprintln!("`--part` must be 1 or 2.");
+        process::exit(1);
+    }
+
+    let path = aoc_cli::get_puzzle_path(day);
+    let cached = fs::read_to_string(&path)
+        .ok()
+        .filter(|puzzle| puzzle::select_part(puzzle, part).is_some());
+
+    let puzzle = cached.unwrap_or_else(|| {
+        fetch(day);
+        fs::read_to_string(&path).unwrap_or_else(|e| {
+            eprintln!("Failed to read puzzle description \"{path}\": {e}");
+            process::exit(1);
+        })
+    });
+
+    // only part two can be missing from a stored description.
+    let Some(description) = puzzle::select_part(&puzzle, part) else {
+        eprintln!("Part two of day {day} is not unlocked yet. Submit part one first.");
+        process::exit(1);
+    };
+
+    let rendered = puzzle::render(&description, terminal_dimensions().0);
+    if pager {
+        page(&rendered);
+    } else {
+        println!("{rendered}");
+    }
+}
@@ -0,0 +1,46 @@
+use std::{fs, process};
+
+use crate::template::answers::Answers;
+use crate::template::timings::Timings;
+use crate::template::{aoc_cli, readme_benchmarks, readme_stars};
+
+const README_PATH: &str = "README.md";
+
+pub fn handle(check: bool) {
+    let Ok(readme) = fs::read_to_string(README_PATH) else {
+        eprintln!("Failed to read \"{README_PATH}\".");
+        process::exit(1);
+    };
+
+    let timings = Timings::read_from_file();
+    let answers = Answers::read_from_file();
+
+    let mut updated = readme.clone();
+    let total_millis = timings.total_millis();
+    let result = readme_stars::update_content(
+        &mut updated,
+        &answers,
+        &readme_stars::listed_days(&answers),
+        aoc_cli::get_year(),
+    )
+    .and_then(|()| readme_benchmarks::update_content(&mut updated, timings, total_millis));
+
+    if let Err(e) = result {
+        eprintln!("Failed to render \"{README_PATH}\": {e:?}");
+        process::exit(1);
+    }
+
+    if check {
+        if updated == readme {
+            println!("\"{README_PATH}\" is up to date.");
+        } else {
+            eprintln!("\"{README_PATH}\" is out of date. Run `cargo readme` to update it.");
+            process::exit(1);
+        }
+    } else if let Err(e) = fs::write(README_PATH, &updated) {
+        eprintln!("Failed to write \"{README_PATH}\": {e}");
+        process::exit(1);
+    } else {
+        println!("Updated \"{README_PATH}\".");
+    }
+}
@@ -1,79 +1,311 @@
 use std::{
-    fs::{File, OpenOptions},
-    io::Write,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
     process,
 };
 
+use crate::template::templates::{self, Placeholders, DEFAULT_RETURN_TYPE, DEFAULT_TEMPLATE};
 use crate::template::Day;
 
-const MODULE_TEMPLATE: &str =
-    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
+/// What scaffolding does with a single path. All actions are planned before anything is written.
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    CreateDir,
+    Create,
+    /// Replace a file, keeping its previous contents to restore on failure.
+    Overwrite(Vec<u8>),
+    Skip(&'static str),
+}
 
-fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error> {
-    let mut file = OpenOptions::new();
-    if overwrite {
-        file.create(true);
-    } else {
-        file.create_new(true);
+#[derive(Debug)]
+struct Step {
+    path: PathBuf,
+    label: &'static str,
+    contents: String,
+    action: Action,
+}
+
+/// Plan the creation of the module file. An existing module is only replaced with `overwrite`.
+fn plan_module(path: &Path, contents: String, overwrite: bool) -> Result<Step, String> {
+    let action = match fs::read(path) {
+        Ok(_) if !overwrite => {
+            return Err(format!(
+                "module file \"{}\" already exists. Use `--overwrite` to replace it.",
+                path.display()
+            ))
+        }
+        Ok(previous) => Action::Overwrite(previous),
+        Err(e) if e.kind() == ErrorKind::NotFound => Action::Create,
+        Err(e) => return Err(format!("failed to read \"{}\": {e}", path.display())),
+    };
+
+    Ok(Step {
+        path: path.into(),
+        label: "module file",
+        contents,
+        action,
+    })
+}
+
+/// Plan the creation of an empty data file. Existing data files are never touched, they may hold a downloaded input.
+fn plan_data_file(path: &Path, label: &'static str) -> Step {
+    let action = match fs::metadata(path) {
+        Ok(metadata) if metadata.len() > 0 => Action::Skip("not empty"),
+        Ok(_) => Action::Skip("already exists"),
+        Err(_) => Action::Create,
+    };
+
+    Step {
+        path: path.into(),
+        label,
+        contents: String::new(),
+        action,
     }
-    file.truncate(true).write(true).open(path)
 }
 
-fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(path)
+/// Prepend steps that create missing parent directories.
+fn with_parent_dirs(steps: Vec<Step>) -> Vec<Step> {
+    let mut planned: Vec<Step> = vec![];
+
+    for step in steps {
+        let missing_parent = step
+            .path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty() && !p.exists())
+            .filter(|p| !planned.iter().any(|s| s.path == *p));
+
+        if step.action == Action::Create {
+            if let Some(parent) = missing_parent {
+                planned.push(Step {
+                    path: parent.into(),
+                    label: "directory",
+                    contents: String::new(),
+                    action: Action::CreateDir,
+                });
+            }
+        }
+
+        planned.push(step);
+    }
+
+    planned
 }
 
-pub fn handle(day: Day, overwrite: bool) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+fn apply_step(step: &Step) -> Result<(), std::io::Error> {
+    match step.action {
+        Action::CreateDir => fs::create_dir(&step.path),
+        Action::Create => fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&step.path)
+            .and_then(|mut file| std::io::Write::write_all(&mut file, step.contents.as_bytes())),
+        Action::Overwrite(_) => fs::write(&step.path, &step.contents),
+        Action::Skip(_) => Ok(()),
+    }
+}
 
-    let mut file = match safe_create_file(&module_path, overwrite) {
-        Ok(file) => file,
-        Err(e) => {
-            eprintln!("Failed to create module file: {e}");
-            process::exit(1);
+fn rollback_step(step: &Step) -> Result<(), std::io::Error> {
+    match &step.action {
+        Action::CreateDir => fs::remove_dir(&step.path),
+        Action::Create => fs::remove_file(&step.path),
+        Action::Overwrite(previous) => fs::write(&step.path, previous),
+        Action::Skip(_) => Ok(()),
+    }
+}
+
+/// Apply all steps in order. If one fails, the steps applied before it are undone in reverse order.
+fn apply(steps: &[Step]) -> Result<(), (usize, std::io::Error)> {
+    for (i, step) in steps.iter().enumerate() {
+        if let Err(e) = apply_step(step) {
+            for applied in steps[..i].iter().rev() {
+                match rollback_step(applied) {
+                    Ok(()) => println!(
+                        "Rolled back {} \"{}\"",
+                        applied.label,
+                        applied.path.display()
+                    ),
+                    Err(e) => eprintln!(
+                        "Failed to roll back {} \"{}\": {e}",
+                        applied.label,
+                        applied.path.display()
+                    ),
+                }
+            }
+            return Err((i, e));
         }
+    }
+    Ok(())
+}
+
+fn describe(step: &Step, dry_run: bool) -> String {
+    let path = step.path.display();
+    let empty = if step.action == Action::Create && step.contents.is_empty() {
+        "empty "
+    } else {
+        ""
     };
 
-    match file.write_all(
-        MODULE_TEMPLATE
-            .replace("%DAY_NUMBER%", &day.into_inner().to_string())
-            .as_bytes(),
-    ) {
-        Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
+    match (&step.action, dry_run) {
+        (Action::CreateDir | Action::Create, false) => {
+            format!("Created {empty}{} \"{path}\"", step.label)
         }
-        Err(e) => {
-            eprintln!("Failed to write module contents: {e}");
-            process::exit(1);
+        (Action::CreateDir | Action::Create, true) => {
+            format!("Would create {empty}{} \"{path}\"", step.label)
         }
+        (Action::Overwrite(_), false) => format!("Overwrote {} \"{path}\"", step.label),
+        (Action::Overwrite(_), true) => format!("Would overwrite {} \"{path}\"", step.label),
+        (Action::Skip(reason), _) => format!("Skipped {} \"{path}\" ({reason})", step.label),
     }
+}
+
+pub fn handle(
+    day: Day,
+    overwrite: bool,
+    dry_run: bool,
+    template: Option<&str>,
+    return_type: Option<&str>,
+) {
+    let input_path = format!("data/inputs/{day}.txt");
+    let example_path = format!("data/examples/{day}.txt");
+    let module_path = format!("src/bin/{day}.rs");
 
-    match create_file(&input_path) {
-        Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
+    let template_name = template.unwrap_or(DEFAULT_TEMPLATE);
+    let module_template = match templates::load(template_name) {
+        Ok(template) => template,
+        Err(templates::Error::NotFound(name, available)) => {
+            eprintln!(
+                "Unknown template \"{name}\". Available templates: {}",
+                available.join(", ")
+            );
+            process::exit(1);
         }
         Err(e) => {
-            eprintln!("Failed to create input file: {e}");
+            eprintln!("Failed to read template \"{template_name}\": {e:?}");
             process::exit(1);
         }
-    }
+    };
 
-    match create_file(&example_path) {
-        Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
-        }
+    let placeholders = Placeholders::for_day(day, return_type.unwrap_or(DEFAULT_RETURN_TYPE));
+    let module_contents = templates::render(&module_template, &placeholders);
+
+    let module = match plan_module(Path::new(&module_path), module_contents, overwrite) {
+        Ok(step) => step,
         Err(e) => {
-            eprintln!("Failed to create example file: {e}");
+            eprintln!("Failed to create module file: {e}");
             process::exit(1);
         }
+    };
+
+    let steps = with_parent_dirs(vec![
+        module,
+        plan_data_file(Path::new(&input_path), "input file"),
+        plan_data_file(Path::new(&example_path), "example file"),
+    ]);
+
+    if dry_run {
+        for step in &steps {
+            println!("{}", describe(step, true));
+        }
+        return;
+    }
+
+    if let Err((i, e)) = apply(&steps) {
+        eprintln!(
+            "Failed to create {} \"{}\": {e}",
+            steps[i].label,
+            steps[i].path.display()
+        );
+        process::exit(1);
+    }
+
+    for step in &steps {
+        println!("{}", describe(step, false));
     }
 
     println!("---");
     println!("🎄 Type `cargo solve {day}` to run your solution.");
 }
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::{apply, plan_data_file, plan_module, with_parent_dirs, Action};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-scaffold-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn keeps_existing_data_files() {
+        let dir = temp_dir("data");
+        fs::write(dir.join("01.txt"), "puzzle input").unwrap();
+        fs::write(dir.join("02.txt"), "").unwrap();
+
+        assert_eq!(
+            plan_data_file(&dir.join("01.txt"), "input").action,
+            Action::Skip("not empty")
+        );
+        assert_eq!(
+            plan_data_file(&dir.join("02.txt"), "input").action,
+            Action::Skip("already exists")
+        );
+        assert_eq!(
+            plan_data_file(&dir.join("03.txt"), "input").action,
+            Action::Create
+        );
+
+        let steps = vec![plan_data_file(&dir.join("01.txt"), "input")];
+        apply(&steps).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("01.txt")).unwrap(),
+            "puzzle input"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn requires_overwrite_for_modules() {
+        let dir = temp_dir("module");
+        let path = dir.join("01.rs");
+        fs::write(&path, "old").unwrap();
+
+        assert!(plan_module(&path, "new".into(), false).is_err());
+
+        let step = plan_module(&path, "new".into(), true).unwrap();
+        assert_eq!(step.action, Action::Overwrite(b"old".to_vec()));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rolls_back_on_failure() {
+        let dir = temp_dir("rollback");
+        let module = dir.join("01.rs");
+        fs::write(&module, "old").unwrap();
+
+        let mut steps = with_parent_dirs(vec![
+            plan_module(&module, "new".into(), true).unwrap(),
+            plan_data_file(&dir.join("inputs").join("01.txt"), "input"),
+            plan_data_file(&dir.join("examples").join("01.txt"), "example"),
+        ]);
+        assert_eq!(steps.len(), 5);
+
+        // make the last step fail by creating the file it expects to be missing.
+        fs::create_dir(dir.join("examples")).unwrap();
+        fs::write(dir.join("examples").join("01.txt"), "").unwrap();
+        steps.remove(3);
+
+        let (failed, _) = apply(&steps).unwrap_err();
+        assert_eq!(failed, 3);
+        assert_eq!(fs::read_to_string(&module).unwrap(), "old");
+        assert!(!dir.join("inputs").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
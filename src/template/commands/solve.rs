@@ -1,11 +1,29 @@
 use std::process::{Command, Stdio};
 
 use crate::template::Day;
+use crate::visualize::image;
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+#[allow(clippy::too_many_arguments)]
+pub fn handle(
+    day: Day,
+    release: bool,
+    dhat: bool,
+    profile: bool,
+    trace: bool,
+    visualize: bool,
+    export: Option<image::Options>,
+    submit_part: Option<u8>,
+) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
-    if dhat {
+    if profile {
+        cmd_args.extend([
+            "--profile".to_string(),
+            "profiling".to_string(),
+            "--features".to_string(),
+            "profile".to_string(),
+        ]);
+    } else if dhat {
         cmd_args.extend([
             "--profile".to_string(),
             "dhat".to_string(),
@@ -16,8 +34,22 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push("--release".to_string());
     }
 
+    let visualize = visualize || export.is_some();
+    if visualize {
+        cmd_args.extend(["--features".to_string(), "visualize".to_string()]);
+    }
+
     cmd_args.push("--".to_string());
 
+    if profile {
+        cmd_args.push("--profile".to_string());
+    } else if trace {
+        cmd_args.push("--trace".to_string());
+    } else if visualize {
+        cmd_args.push("--visualize".to_string());
+        cmd_args.extend(export.iter().flat_map(image::Options::to_args));
+    }
+
     if let Some(submit_part) = submit_part {
         cmd_args.push("--submit".to_string());
         cmd_args.push(submit_part.to_string());
@@ -0,0 +1,137 @@
+use crate::template::answers::Answers;
+use crate::template::meta::Meta;
+use crate::template::{all_days, aoc_cli, clock, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+const STAR: char = '★';
+const NO_STAR: char = '·';
+
+/// Stars earned for a day. Answers accepted before submissions were recorded count as well.
+fn stars(meta: &Meta, answers: &Answers) -> [bool; 2] {
+    let answer = answers.get(meta.day);
+    [
+        meta.solved_at(1).is_some() || answer.is_some_and(|a| a.part_1.is_some()),
+        meta.solved_at(2).is_some() || answer.is_some_and(|a| a.part_2.is_some()),
+    ]
+}
+
+fn render_cell(meta: &Meta, answers: &Answers, now: u64) -> String {
+    let day = meta.day.into_inner();
+    if !meta.is_unlocked(now) {
+        return format!("{day:>2} {ANSI_ITALIC}--{ANSI_RESET}");
+    }
+
+    let symbols: String = stars(meta, answers)
+        .iter()
+        .map(|&s| if s { STAR } else { NO_STAR })
+        .collect();
+
+    format!("{day:>2} {ANSI_BOLD}{symbols}{ANSI_RESET}")
+}
+
+/// Time between unlock and the first correct submission of a part, e.g. `+1h 12m`.
+fn render_solve_time(meta: &Meta, part: u8) -> String {
+    meta.solved_at(part).map_or_else(
+        || "-".into(),
+        |at| {
+            format!(
+                "+{}",
+                clock::format_span(at.saturating_sub(meta.unlock_time))
+            )
+        },
+    )
+}
+
+fn render(metas: &[Meta], answers: &Answers, year: u16, now: u64) -> String {
+    let total: usize = metas
+        .iter()
+        .map(|m| stars(m, answers).iter().filter(|&&s| s).count())
+        .sum();
+
+    let mut lines: Vec<String> = vec![
+        format!("{ANSI_BOLD}Advent of Code {year}{ANSI_RESET}"),
+        format!("Progress: {total}/50 {STAR}"),
+        String::new(),
+    ];
+
+    for week in metas.chunks(5) {
+        let cells: Vec<String> = week.iter().map(|m| render_cell(m, answers, now)).collect();
+        lines.push(cells.join("    "));
+    }
+
+    let started: Vec<&Meta> = metas
+        .iter()
+        .filter(|m| m.title.is_some() || stars(m, answers).contains(&true))
+        .collect();
+
+    if !started.is_empty() {
+        lines.push(String::new());
+        lines.push(format!(
+            "{:>3}  {:<2}  {:<32}  {:>10}  {:>10}",
+            "Day", "", "Title", "Part 1", "Part 2"
+        ));
+
+        for meta in started {
+            let symbols: String = stars(meta, answers)
+                .iter()
+                .map(|&s| if s { STAR } else { NO_STAR })
+                .collect();
+
+            lines.push(format!(
+                "{:>3}  {symbols}  {:<32}  {:>10}  {:>10}",
+                meta.day.into_inner(),
+                meta.title.as_deref().unwrap_or("-"),
+                render_solve_time(meta, 1),
+                render_solve_time(meta, 2),
+            ));
+        }
+    }
+
+    if let Some(next) = metas.iter().find(|m| !m.is_unlocked(now)) {
+        lines.push(String::new());
+        lines.push(format!(
+            "{ANSI_ITALIC}Day {} unlocks in {}.{ANSI_RESET}",
+            next.day.into_inner(),
+            clock::format_span(next.unlock_time - now)
+        ));
+    }
+
+    lines.join("\n")
+}
+
+pub fn handle() {
+    let year = aoc_cli::get_year().unwrap_or_else(clock::current_year);
+    let answers = Answers::read_from_file();
+    let metas: Vec<Meta> = all_days().map(Meta::read_from_file).collect();
+
+    println!("{}", render(&metas, &answers, year, clock::now()));
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::render;
+    use crate::template::{all_days, answers::Answers, meta::Meta};
+    use crate::{day, template::ANSI_BOLD, template::ANSI_ITALIC, template::ANSI_RESET};
+
+    #[test]
+    fn renders_calendar() {
+        let mut metas: Vec<Meta> = all_days().map(|day| Meta::new(day, 2024)).collect();
+        metas[0].title = Some("Historian Hysteria".into());
+        metas[0].record_submission(1, true);
+        metas[0].submissions[0].timestamp = metas[0].unlock_time + 750;
+
+        let answers = Answers::default().record(day!(2), 2, "4");
+        // noon on december 3rd, server time.
+        let now = metas[2].unlock_time + 12 * 3600;
+
+        let output = render(&metas, &answers, 2024, now);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[1], "Progress: 2/50 ★");
+        assert!(lines[3].starts_with(&format!(
+            " 1 {ANSI_BOLD}★·{ANSI_RESET}     2 {ANSI_BOLD}·★{ANSI_RESET}     3 {ANSI_BOLD}··{ANSI_RESET}     4 {ANSI_ITALIC}--"
+        )));
+        assert!(output.contains("  1  ★·  Historian Hysteria"));
+        assert!(output.contains("+12m 30s"));
+        assert!(output.ends_with(&format!("Day 4 unlocks in 12h 0m.{ANSI_RESET}")));
+    }
+}
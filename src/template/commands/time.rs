@@ -1,10 +1,48 @@
 use std::collections::HashSet;
+use std::path::PathBuf;
+use std::process;
 
+use crate::template::export::{self, Format};
 use crate::template::run_multi::run_multi;
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::{all_days, readme_benchmarks, scaling, Day};
+
+pub fn handle(
+    day: Option<Day>,
+    run_all: bool,
+    store: bool,
+    scale: bool,
+    export: Option<(Format, PathBuf)>,
+) {
+    if let Some((format, path)) = export {
+        let timings = Timings::read_from_file();
+
+        if let Err(e) = export::write(&timings, format, &path) {
+            eprintln!("Failed to export benchmarks: {e:?}");
+            process::exit(1);
+        }
+
+        println!(
+            "Exported benchmarks for {} day(s) to \"{}\".",
+            timings.data.len(),
+            path.display()
+        );
+        return;
+    }
+
+    if scale {
+        let Some(day) = day else {
+            eprintln!("`--scale` requires a day, e.g. `cargo time 20 --scale`.");
+            process::exit(1);
+        };
+
+        if let Err(e) = scaling::run(day) {
+            eprintln!("Failed to run scaling benchmark: {e:?}");
+            process::exit(1);
+        }
+        return;
+    }
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
     let stored_timings = Timings::read_from_file();
 
     let days_to_run = day.map_or_else(
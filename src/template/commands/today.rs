@@ -0,0 +1,105 @@
+use std::{io::Write, process, thread, time::Duration};
+
+use crate::template::commands::{download, read, scaffold};
+use crate::template::meta::Meta;
+use crate::template::{aoc_cli, clock, Day, ANSI_BOLD, ANSI_RESET};
+
+/// The puzzle input is not always available the very second a puzzle unlocks.
+const DOWNLOAD_ATTEMPTS: u32 = 10;
+const DOWNLOAD_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+fn print_countdown(day: Day, unlock_time: u64) {
+    loop {
+        let now = clock::now();
+        if now >= unlock_time {
+            break;
+        }
+
+        print!(
+            "\r{ANSI_BOLD}Day {}{ANSI_RESET} unlocks in {}.\x1b[K",
+            day.into_inner(),
+            clock::format_span(unlock_time - now)
+        );
+        let _ = std::io::stdout().flush();
+        thread::sleep(Duration::from_secs(1));
+    }
+    println!();
+}
+
+fn download_with_retries(day: Day) -> Result<(), aoc_cli::AocCommandError> {
+    let mut attempt = 1;
+    loop {
+        match aoc_cli::download(day) {
+            Ok(_) => return Ok(()),
+            Err(aoc_cli::AocCommandError::BadExitStatus(_)) if attempt < DOWNLOAD_ATTEMPTS => {
+                eprintln!(
+                    "Puzzle not available yet, retrying in {}s ({attempt}/{DOWNLOAD_ATTEMPTS}).",
+                    DOWNLOAD_RETRY_DELAY.as_secs()
+                );
+                attempt += 1;
+                thread::sleep(DOWNLOAD_RETRY_DELAY);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Wait for the next puzzle of the configured year to unlock, then download, scaffold and read it.
+fn handle_wait() {
+    if aoc_cli::check().is_err() {
+        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+        process::exit(1);
+    }
+
+    let year = aoc_cli::get_year();
+    let Some((unlock_year, day, unlock_time)) = clock::next_unlock(clock::now(), year) else {
+        // a pinned year is never switched, downloading would overwrite the inputs of the same day.
+        eprintln!(
+            "All puzzles of {} are unlocked already. Set AOC_YEAR to the coming year or unset it \
+            to wait for the next puzzle, or use `scaffold` with a specific day.",
+            year.unwrap_or_else(clock::current_year)
+        );
+        process::exit(1);
+    };
+
+    let day = Day::new(day).unwrap();
+    println!("🎄 Waiting for day {} of {unlock_year}.", day.into_inner());
+    print_countdown(day, unlock_time);
+
+    // download first, so the scaffolded module picks up title and example answers.
+    let downloaded = download_with_retries(day);
+    if let Err(e) = &downloaded {
+        eprintln!("failed to call aoc-cli: {e}");
+    } else if let Err(e) = Meta::read_from_file(day).store_file() {
+        eprintln!("Failed to store puzzle metadata: {e}");
+    }
+
+    scaffold::handle(day, false, false, None, None);
+
+    if downloaded.is_err() {
+        process::exit(1);
+    }
+    read::handle(day, None, true);
+}
+
+pub fn handle(wait: bool) {
+    if wait {
+        return handle_wait();
+    }
+
+    match Day::today() {
+        Some(day) => {
+            scaffold::handle(day, false, false, None, None);
+            download::handle(day);
+            read::handle(day, None, true);
+        }
+        None => {
+            eprintln!(
+                "`today` command can only be run between the 1st and \
+                the 25th of december. Use `--wait` to wait for the next puzzle, \
+                or `scaffold` with a specific day."
+            );
+            process::exit(1)
+        }
+    }
+}
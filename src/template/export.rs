@@ -0,0 +1,369 @@
+/// Module that exports stored timings for use outside of the readme.
+/// Supported formats are CSV, a standalone Markdown table, a self-contained HTML report and versioned JSON.
+use std::{collections::HashMap, fmt::Write, fs, io, path::Path, str::FromStr};
+
+use tinyjson::JsonValue;
+
+use crate::template::alloc;
+use crate::template::timings::{PartTiming, Timing, Timings};
+
+/// Version of the JSON export. Bump this when the shape of the document changes.
+pub const JSON_SCHEMA_VERSION: u8 = 1;
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum Error {
+    Serialize(String),
+    IO(io::Error),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Markdown,
+    Html,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(Format::Csv),
+            "md" | "markdown" => Ok(Format::Markdown),
+            "html" => Ok(Format::Html),
+            "json" => Ok(Format::Json),
+            _ => Err(format!(
+                "unknown export format `{s}`, expected one of: csv, md, html, json."
+            )),
+        }
+    }
+}
+
+/// Write `timings` to `path` in the given format.
+pub fn write(timings: &Timings, format: Format, path: &Path) -> Result<(), Error> {
+    let content = render(timings, format)?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+pub fn render(timings: &Timings, format: Format) -> Result<String, Error> {
+    match format {
+        Format::Csv => Ok(to_csv(timings)),
+        Format::Markdown => Ok(to_markdown(timings)),
+        Format::Html => Ok(to_html(timings)),
+        Format::Json => JsonValue::from(to_json(timings))
+            .format()
+            .map_err(|e| Error::Serialize(e.to_string())),
+    }
+}
+
+fn format_part(part: Option<PartTiming>) -> String {
+    part.map_or_else(|| "-".into(), |p| p.to_string())
+}
+
+fn format_millis(nanos: f64) -> String {
+    format!("{:.2}ms", nanos / 1_000_000_f64)
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn to_csv(timings: &Timings) -> String {
+    let mut s = String::from(
+        "day,part_1_nanos,part_1_samples,part_2_nanos,part_2_samples,total_nanos,peak_bytes\n",
+    );
+
+    let field = |x: Option<String>| x.unwrap_or_default();
+
+    for timing in &timings.data {
+        let _ = writeln!(
+            s,
+            "{},{},{},{},{},{},{}",
+            timing.day.into_inner(),
+            field(timing.part_1.map(|p| p.nanos.to_string())),
+            field(timing.part_1.and_then(|p| p.samples).map(|x| x.to_string())),
+            field(timing.part_2.map(|p| p.nanos.to_string())),
+            field(timing.part_2.and_then(|p| p.samples).map(|x| x.to_string())),
+            timing.total_nanos,
+            timing.peak_bytes.map(|x| x.to_string()).unwrap_or_default(),
+        );
+    }
+
+    s
+}
+
+fn to_markdown(timings: &Timings) -> String {
+    let mut lines: Vec<String> = vec![
+        "# Benchmarks".into(),
+        String::new(),
+        "| Day | Part 1 | Part 2 | Total | Memory |".into(),
+        "| :---: | :---: | :---: | :---: | :---: |".into(),
+    ];
+
+    for timing in &timings.data {
+        lines.push(format!(
+            "| Day {} | `{}` | `{}` | `{}` | `{}` |",
+            timing.day.into_inner(),
+            format_part(timing.part_1),
+            format_part(timing.part_2),
+            format_millis(timing.total_nanos),
+            timing
+                .peak_bytes
+                .map_or_else(|| "-".into(), alloc::format_bytes),
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push(format!("**Total: {:.2}ms**", timings.total_millis()));
+    lines.push(String::new());
+
+    lines.join("\n")
+}
+
+/* -------------------------------------------------------------------------- */
+
+const HTML_STYLE: &str = "\
+body { font-family: system-ui, sans-serif; margin: 2rem auto; max-width: 56rem; color: #1f2328; }
+table { border-collapse: collapse; width: 100%; }
+th, td { padding: 0.3rem 0.6rem; border-bottom: 1px solid #d0d7de; text-align: left; white-space: nowrap; }
+td.chart { width: 100%; }
+.bar { height: 0.6rem; margin: 0.15rem 0; border-radius: 2px; }
+.part-1 { background: #2da44e; }
+.part-2 { background: #bf8700; }
+.legend span { display: inline-block; width: 0.8rem; height: 0.8rem; margin: 0 0.3rem 0 1rem; }";
+
+/// Width of a bar in percent of the slowest part. Uses a log scale, as timings span several magnitudes.
+fn bar_width(nanos: f64, max_nanos: f64) -> f64 {
+    if nanos <= 0.0 || max_nanos <= 1.0 {
+        return 0.0;
+    }
+    ((nanos.max(1.0).ln() / max_nanos.ln()) * 100.0).clamp(1.0, 100.0)
+}
+
+fn to_html(timings: &Timings) -> String {
+    let max_nanos = timings
+        .data
+        .iter()
+        .flat_map(|t| [t.part_1, t.part_2])
+        .flatten()
+        .map(|p| p.nanos)
+        .fold(0_f64, f64::max);
+
+    let mut rows = String::new();
+
+    for timing in &timings.data {
+        let mut bars = String::new();
+        for (class, part) in [("part-1", &timing.part_1), ("part-2", &timing.part_2)] {
+            if let Some(part) = part {
+                let _ = write!(
+                    bars,
+                    r#"<div class="bar {class}" style="width: {:.1}%" title="{part}"></div>"#,
+                    bar_width(part.nanos, max_nanos),
+                );
+            }
+        }
+
+        let _ = writeln!(
+            rows,
+            "<tr><td>Day {}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"chart\">{bars}</td></tr>",
+            timing.day.into_inner(),
+            format_part(timing.part_1),
+            format_part(timing.part_2),
+            format_millis(timing.total_nanos),
+            timing
+                .peak_bytes
+                .map_or_else(|| "-".into(), alloc::format_bytes),
+        );
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Benchmarks</title>
+<style>
+{HTML_STYLE}
+</style>
+</head>
+<body>
+<h1>Benchmarks</h1>
+<p class="legend"><span class="part-1"></span>Part 1<span class="part-2"></span>Part 2 <em>(log scale)</em></p>
+<table>
+<thead><tr><th>Day</th><th>Part 1</th><th>Part 2</th><th>Total</th><th>Memory</th><th></th></tr></thead>
+<tbody>
+{rows}</tbody>
+</table>
+<p><strong>Total: {:.2}ms</strong></p>
+</body>
+</html>
+"#,
+        timings.total_millis()
+    )
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn number_or_null(x: Option<f64>) -> JsonValue {
+    x.map_or(JsonValue::Null, JsonValue::Number)
+}
+
+fn timing_to_json(timing: &Timing) -> JsonValue {
+    let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+    map.insert(
+        "day".into(),
+        JsonValue::Number(f64::from(timing.day.into_inner())),
+    );
+    #[allow(clippy::cast_precision_loss)]
+    for (key, part) in [("part_1", timing.part_1), ("part_2", timing.part_2)] {
+        map.insert(
+            format!("{key}_nanos"),
+            number_or_null(part.map(|p| p.nanos)),
+        );
+        map.insert(
+            format!("{key}_samples"),
+            number_or_null(part.and_then(|p| p.samples).map(|x| x as f64)),
+        );
+    }
+    map.insert("total_nanos".into(), JsonValue::Number(timing.total_nanos));
+    #[allow(clippy::cast_precision_loss)]
+    map.insert(
+        "peak_bytes".into(),
+        number_or_null(timing.peak_bytes.map(|x| x as f64)),
+    );
+
+    JsonValue::Object(map)
+}
+
+fn to_json(timings: &Timings) -> HashMap<String, JsonValue> {
+    let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+    map.insert(
+        "version".into(),
+        JsonValue::Number(f64::from(JSON_SCHEMA_VERSION)),
+    );
+    map.insert(
+        "total_nanos".into(),
+        JsonValue::Number(timings.data.iter().map(|t| t.total_nanos).sum()),
+    );
+    map.insert(
+        "days".into(),
+        JsonValue::Array(timings.data.iter().map(timing_to_json).collect()),
+    );
+
+    map
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::collections::HashMap;
+
+    use tinyjson::JsonValue;
+
+    use super::{render, Format};
+    use crate::{
+        day,
+        template::timings::{PartTiming, Timing, Timings},
+    };
+
+    fn get_mock_timings() -> Timings {
+        Timings {
+            data: vec![
+                Timing {
+                    day: day!(1),
+                    part_1: Some(PartTiming {
+                        nanos: 74.0,
+                        samples: Some(10000),
+                    }),
+                    part_2: Some(PartTiming {
+                        nanos: 1_500_000.0,
+                        samples: Some(660),
+                    }),
+                    total_nanos: 1_500_074.0,
+                    peak_bytes: Some(2048),
+                },
+                Timing {
+                    day: day!(2),
+                    part_1: Some(PartTiming {
+                        nanos: 2000.0,
+                        samples: None,
+                    }),
+                    part_2: None,
+                    total_nanos: 2000.0,
+                    peak_bytes: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!("csv".parse(), Ok(Format::Csv));
+        assert_eq!("md".parse(), Ok(Format::Markdown));
+        assert_eq!("HTML".parse(), Ok(Format::Html));
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert!("xml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn exports_csv() {
+        let csv = render(&get_mock_timings(), Format::Csv).unwrap();
+        assert_eq!(
+            csv,
+            "day,part_1_nanos,part_1_samples,part_2_nanos,part_2_samples,total_nanos,peak_bytes\n\
+            1,74,10000,1500000,660,1500074,2048\n\
+            2,2000,,,,2000,\n"
+        );
+    }
+
+    #[test]
+    fn exports_markdown() {
+        let md = render(&get_mock_timings(), Format::Markdown).unwrap();
+        assert!(md.starts_with("# Benchmarks\n"));
+        assert!(md.contains("| Day 1 | `74.0ns` | `1.5ms` | `1.50ms` | `2.0 KiB` |"));
+        assert!(md.contains("| Day 2 | `2.0µs` | `-` | `0.00ms` | `-` |"));
+        assert!(md.contains("**Total: 1.50ms**"));
+    }
+
+    #[test]
+    fn exports_html() {
+        let html = render(&get_mock_timings(), Format::Html).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<style>"));
+        assert!(html.contains(r#"<div class="bar part-2" style="width: 100.0%" title="1.5ms">"#));
+        assert_eq!(html.matches("<tr><td>Day").count(), 2);
+    }
+
+    #[test]
+    fn exports_json() {
+        let json = render(&get_mock_timings(), Format::Json).unwrap();
+        let json: JsonValue = json.parse().unwrap();
+        let doc = json.get::<HashMap<String, JsonValue>>().unwrap();
+
+        assert_eq!(doc["version"], JsonValue::Number(1.0));
+
+        let days = doc["days"].get::<Vec<JsonValue>>().unwrap();
+        let day_1 = days[0].get::<HashMap<String, JsonValue>>().unwrap();
+        assert_eq!(day_1["day"], JsonValue::Number(1.0));
+        assert_eq!(day_1["part_1_nanos"], JsonValue::Number(74.0));
+        assert_eq!(day_1["part_1_samples"], JsonValue::Number(10000.0));
+        assert_eq!(day_1["part_2_nanos"], JsonValue::Number(1_500_000.0));
+
+        let day_2 = days[1].get::<HashMap<String, JsonValue>>().unwrap();
+        assert_eq!(day_2["part_2_nanos"], JsonValue::Null);
+        assert_eq!(day_2["peak_bytes"], JsonValue::Null);
+    }
+}
@@ -0,0 +1,283 @@
+/// Module that keeps a record per day in `data/meta/DD.json`: the puzzle title, when it unlocks and
+/// when answers were submitted. Used by `cargo status` to render a calendar of progress.
+use std::{collections::HashMap, fs, io::Error, path::Path, str::FromStr};
+use tinyjson::JsonValue;
+
+use crate::template::{aoc_cli, clock, templates, Day};
+
+const META_DIR: &str = "data/meta";
+
+/// A single answer submitted via `cargo solve <day> --submit <part>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Submission {
+    pub part: u8,
+    pub timestamp: u64,
+    pub correct: bool,
+}
+
+/// Represents what is known about the puzzle of a single day.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Meta {
+    pub day: Day,
+    pub title: Option<String>,
+    pub unlock_time: u64,
+    pub submissions: Vec<Submission>,
+}
+
+#[must_use]
+pub fn get_path_for_meta(day: Day) -> String {
+    format!("{META_DIR}/{day}.json")
+}
+
+impl Meta {
+    #[must_use]
+    pub fn new(day: Day, year: u16) -> Self {
+        Meta {
+            day,
+            title: None,
+            unlock_time: clock::unlock_time(year, day.into_inner()),
+            submissions: vec![],
+        }
+    }
+
+    /// Rehydrate the record of `day` from its JSON file, or start a new one.
+    /// A missing title is filled in from the puzzle description, if it has been downloaded.
+    pub fn read_from_file(day: Day) -> Self {
+        let year = aoc_cli::get_year().unwrap_or_else(clock::current_year);
+
+        let mut meta = fs::read_to_string(get_path_for_meta(day))
+            .map_err(|x| x.to_string())
+            .and_then(Meta::try_from)
+            .unwrap_or_else(|_| Meta::new(day, year));
+
+        if meta.title.is_none() {
+            meta.title = fs::read_to_string(format!("data/puzzles/{day}.md"))
+                .ok()
+                .and_then(|puzzle| templates::parse_title(&puzzle));
+        }
+
+        meta
+    }
+
+    /// Dehydrate the record to its JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        fs::create_dir_all(Path::new(META_DIR))?;
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(get_path_for_meta(self.day))?;
+        json.format_to(&mut file)
+    }
+
+    pub fn record_submission(&mut self, part: u8, correct: bool) {
+        self.submissions.push(Submission {
+            part,
+            timestamp: clock::now(),
+            correct,
+        });
+    }
+
+    /// Time of the first correct submission for `part`.
+    pub fn solved_at(&self, part: u8) -> Option<u64> {
+        self.submissions
+            .iter()
+            .filter(|s| s.part == part && s.correct)
+            .map(|s| s.timestamp)
+            .min()
+    }
+
+    pub fn stars(&self) -> u8 {
+        u8::from(self.solved_at(1).is_some()) + u8::from(self.solved_at(2).is_some())
+    }
+
+    pub fn is_unlocked(&self, now: u64) -> bool {
+        now >= self.unlock_time
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Meta> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: Meta) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert(
+            "title".into(),
+            value
+                .title
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+        map.insert(
+            "unlock_time".into(),
+            JsonValue::Number(value.unlock_time as f64),
+        );
+        map.insert("stars".into(), JsonValue::Number(f64::from(value.stars())));
+        map.insert(
+            "submissions".into(),
+            JsonValue::Array(value.submissions.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Meta {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected meta.day to be a Day struct.")?;
+
+        let title = json
+            .get("title")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected meta.title to be null or string.")?;
+
+        let unlock_time = json
+            .get("unlock_time")
+            .and_then(|v| v.get::<f64>())
+            .map(|&x| x as u64)
+            .ok_or("Expected meta.unlock_time to be a number.")?;
+
+        let submissions = json
+            .get("submissions")
+            .and_then(|v| v.get::<Vec<JsonValue>>())
+            .ok_or("Expected meta.submissions to be an array.")?
+            .iter()
+            .map(Submission::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Meta {
+            day,
+            title: title.cloned(),
+            unlock_time,
+            submissions,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Submission> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &Submission) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("part".into(), JsonValue::Number(f64::from(value.part)));
+        map.insert(
+            "timestamp".into(),
+            JsonValue::Number(value.timestamp as f64),
+        );
+        map.insert("correct".into(), JsonValue::Boolean(value.correct));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Submission {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected submission to be a JSON object.")?;
+
+        let part = json
+            .get("part")
+            .and_then(|v| v.get::<f64>())
+            .map(|&x| x as u8)
+            .filter(|part| matches!(part, 1 | 2))
+            .ok_or("Expected submission.part to be 1 or 2.")?;
+
+        let timestamp = json
+            .get("timestamp")
+            .and_then(|v| v.get::<f64>())
+            .map(|&x| x as u64)
+            .ok_or("Expected submission.timestamp to be a number.")?;
+
+        let correct = json
+            .get("correct")
+            .and_then(|v| v.get::<bool>().copied())
+            .ok_or("Expected submission.correct to be a boolean.")?;
+
+        Ok(Submission {
+            part,
+            timestamp,
+            correct,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Meta, Submission};
+    use crate::day;
+
+    fn get_mock_meta() -> Meta {
+        Meta {
+            day: day!(7),
+            title: Some("Bridge Repair".into()),
+            unlock_time: 1_733_547_600,
+            submissions: vec![
+                Submission {
+                    part: 1,
+                    timestamp: 1_733_548_000,
+                    correct: false,
+                },
+                Submission {
+                    part: 1,
+                    timestamp: 1_733_548_600,
+                    correct: true,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let meta = get_mock_meta();
+        let json = tinyjson::JsonValue::from(meta.clone()).stringify().unwrap();
+        assert_eq!(Meta::try_from(json).unwrap(), meta);
+    }
+
+    #[test]
+    fn counts_stars() {
+        let mut meta = get_mock_meta();
+        assert_eq!(meta.solved_at(1), Some(1_733_548_600));
+        assert_eq!(meta.solved_at(2), None);
+        assert_eq!(meta.stars(), 1);
+
+        meta.record_submission(2, true);
+        assert_eq!(meta.stars(), 2);
+    }
+
+    #[test]
+    fn computes_unlock_times() {
+        let meta = Meta::new(day!(7), 2024);
+        assert_eq!(meta.unlock_time, 1_733_547_600);
+        assert!(!meta.is_unlocked(1_733_547_599));
+        assert!(meta.is_unlocked(1_733_547_600));
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_malformed_meta() {
+        let json = r#"{ "day": "07", "title": null, "unlock_time": 0, "submissions": [{ "part": 3, "timestamp": 0, "correct": true }] }"#.to_string();
+        Meta::try_from(json).unwrap();
+    }
+}
@@ -1,14 +1,24 @@
 use std::{env, fs};
 
+pub mod alloc;
 pub mod aoc_cli;
 pub mod commands;
 pub mod runner;
 
 pub use day::*;
 
+mod answers;
+mod clock;
 mod day;
+pub mod export;
+mod meta;
+mod profiling;
+mod puzzle;
 mod readme_benchmarks;
+mod readme_stars;
 mod run_multi;
+mod scaling;
+mod templates;
 mod timings;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
@@ -59,10 +69,24 @@ macro_rules! solution {
         #[global_allocator]
         static ALLOC: dhat::Alloc = dhat::Alloc;
 
+        #[cfg(not(feature = "dhat-heap"))]
+        #[global_allocator]
+        static ALLOC: $crate::template::alloc::PeakAlloc = $crate::template::alloc::PeakAlloc;
+
         fn main() {
             use $crate::template::runner::*;
             let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+            if std::env::args().any(|x| x == "--scale") {
+                $( run_part_scaled($func, &input, DAY, $part); )*
+            } else if std::env::args().any(|x| x == "--profile") {
+                $( run_part_profiled($func, &input, DAY, $part); )*
+            } else if std::env::args().any(|x| x == "--trace") {
+                $( run_part_traced($func, &input, DAY, $part); )*
+            } else if std::env::args().any(|x| x == "--visualize") {
+                $( run_part_visualized($func, &input, DAY, $part); )*
+            } else {
+                $( run_part($func, &input, DAY, $part); )*
+            }
         }
     };
 }
@@ -0,0 +1,116 @@
+/// Module that profiles a solution part with a sampling CPU profiler.
+/// The collected stacks are written as a folded-stack file and rendered to an SVG flamegraph.
+use std::{fmt::Write, fs, io, time::Duration};
+
+use crate::template::Day;
+
+/// Wall-clock time a part is run in a loop for while being sampled.
+pub const PROFILE_BUDGET: Duration = Duration::from_secs(5);
+
+/// Sampling frequency in Hz. Deliberately not a round number to avoid lockstep with periodic work.
+#[cfg(feature = "profile")]
+const SAMPLE_FREQUENCY: i32 = 997;
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum Error {
+    FeatureDisabled,
+    Profiler(String),
+    IO(io::Error),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+/// Summary of a profiling run.
+pub struct Profile {
+    pub iterations: u64,
+    pub samples: isize,
+    pub folded_path: String,
+    pub svg_path: String,
+}
+
+#[must_use]
+pub fn get_path_for_profile(day: Day, part: u8, extension: &str) -> String {
+    format!("data/profiles/{day}-part{part}.{extension}")
+}
+
+/// Run `func` repeatedly for [`PROFILE_BUDGET`] under a sampling profiler,
+/// then write `data/profiles/<day>-part<n>.folded` and `data/profiles/<day>-part<n>.svg`.
+#[cfg(feature = "profile")]
+pub fn profile(mut func: impl FnMut(), day: Day, part: u8) -> Result<Profile, Error> {
+    use std::time::Instant;
+
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(SAMPLE_FREQUENCY)
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()
+        .map_err(|e| Error::Profiler(e.to_string()))?;
+
+    let timer = Instant::now();
+    let mut iterations = 0;
+    while iterations == 0 || timer.elapsed() < PROFILE_BUDGET {
+        func();
+        iterations += 1;
+    }
+
+    let report = guard
+        .report()
+        .build()
+        .map_err(|e| Error::Profiler(e.to_string()))?;
+
+    let lines = folded_lines(&report);
+    let samples = report.data.values().sum();
+
+    fs::create_dir_all("data/profiles")?;
+
+    let folded_path = get_path_for_profile(day, part, "folded");
+    fs::write(&folded_path, lines.join("\n") + "\n")?;
+
+    let svg_path = get_path_for_profile(day, part, "svg");
+    let svg = fs::File::create(&svg_path)?;
+    let mut options = pprof::flamegraph::Options::default();
+    options.title = format!("Day {day} - Part {part}");
+    if !lines.is_empty() {
+        pprof::flamegraph::from_lines(&mut options, lines.iter().map(String::as_str), svg)
+            .map_err(|e| Error::Profiler(e.to_string()))?;
+    }
+
+    Ok(Profile {
+        iterations,
+        samples,
+        folded_path,
+        svg_path,
+    })
+}
+
+#[cfg(not(feature = "profile"))]
+pub fn profile(_func: impl FnMut(), _day: Day, _part: u8) -> Result<Profile, Error> {
+    Err(Error::FeatureDisabled)
+}
+
+/// Convert a report to the folded-stack format used by `inferno` and `flamegraph.pl`:
+/// one `thread;outer;...;inner <count>` line per unique stack.
+#[cfg(feature = "profile")]
+fn folded_lines(report: &pprof::Report) -> Vec<String> {
+    let mut lines: Vec<String> = report
+        .data
+        .iter()
+        .map(|(frames, count)| {
+            let mut line = frames.thread_name_or_id();
+            for frame in frames.frames.iter().rev() {
+                for symbol in frame.iter().rev() {
+                    let _ = write!(line, ";{symbol}");
+                }
+            }
+            let _ = write!(line, " {count}");
+            line
+        })
+        .collect();
+
+    lines.sort_unstable();
+    lines
+}
@@ -0,0 +1,314 @@
+/// Module that renders puzzle descriptions stored by aoc-cli in `data/puzzles/DD.md` for the terminal.
+/// Emphasized text (`<em>`, usually answers) is printed bold, code is printed italic.
+use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+const PART_TWO_HEADING: &str = "--- Part Two ---";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Style {
+    bold: bool,
+    italic: bool,
+}
+
+impl Style {
+    fn codes(self) -> String {
+        let mut codes = String::new();
+        if self.bold {
+            codes.push_str(ANSI_BOLD);
+        }
+        if self.italic {
+            codes.push_str(ANSI_ITALIC);
+        }
+        codes
+    }
+}
+
+/// A word of a paragraph, every character carries its own style.
+type Word = Vec<(char, Style)>;
+
+/* -------------------------------------------------------------------------- */
+
+/// The part of the description for `part`, or the whole description without one.
+/// Returns `None` for part two if it is not contained in the description yet.
+#[must_use]
+pub fn select_part(puzzle: &str, part: Option<u8>) -> Option<String> {
+    let lines: Vec<&str> = puzzle.lines().collect();
+    let part_two = lines.iter().position(|l| l.contains(PART_TWO_HEADING));
+
+    let selected = match (part, part_two) {
+        (Some(2), Some(start)) => &lines[start..],
+        (Some(2), None) => return None,
+        (Some(_), Some(start)) => &lines[..start],
+        _ => &lines[..],
+    };
+
+    Some(selected.join("\n"))
+}
+
+/// Render the markdown of a puzzle description, wrapped to `width` columns.
+#[must_use]
+pub fn render(puzzle: &str, width: usize) -> String {
+    let mut output: Vec<String> = vec![];
+    let mut paragraph: Vec<&str> = vec![];
+    let mut lines = puzzle.lines().peekable();
+
+    let flush = |paragraph: &mut Vec<&str>, output: &mut Vec<String>| {
+        if !paragraph.is_empty() {
+            let text = paragraph.join(" ");
+            output.extend(wrap(&parse_inline(&text), width, ""));
+            output.push(String::new());
+            paragraph.clear();
+        }
+    };
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("```") {
+            flush(&mut paragraph, &mut output);
+            for code in lines.by_ref() {
+                if code.trim().starts_with("```") {
+                    break;
+                }
+                output.push(format!("    {ANSI_ITALIC}{code}{ANSI_RESET}"));
+            }
+            output.push(String::new());
+        } else if line.starts_with("    ") && paragraph.is_empty() {
+            output.push(format!("{ANSI_ITALIC}{line}{ANSI_RESET}"));
+            if lines.peek().is_none_or(|next| !next.starts_with("    ")) {
+                output.push(String::new());
+            }
+        } else if is_heading_underline(trimmed) && !paragraph.is_empty() {
+            // setext heading: the paragraph collected so far is the heading text.
+            let heading = paragraph.join(" ");
+            paragraph.clear();
+            output.push(format!("{ANSI_BOLD}{}{ANSI_RESET}", unescape(&heading)));
+            output.push(String::new());
+        } else if let Some(heading) = trimmed.strip_prefix('#') {
+            flush(&mut paragraph, &mut output);
+            let heading = heading.trim_start_matches('#').trim();
+            output.push(format!("{ANSI_BOLD}{}{ANSI_RESET}", unescape(heading)));
+            output.push(String::new());
+        } else if let Some(item) = ["* ", "- "].iter().find_map(|p| trimmed.strip_prefix(p)) {
+            flush(&mut paragraph, &mut output);
+            output.extend(wrap(&parse_inline(item), width, "  • "));
+            if lines
+                .peek()
+                .is_none_or(|next| next.trim().is_empty() || !is_list_item(next))
+            {
+                output.push(String::new());
+            }
+        } else if trimmed.is_empty() {
+            flush(&mut paragraph, &mut output);
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+
+    flush(&mut paragraph, &mut output);
+
+    while output.last().is_some_and(String::is_empty) {
+        output.pop();
+    }
+
+    // collapse runs of blank lines left by consecutive blocks.
+    output.dedup_by(|a, b| a.is_empty() && b.is_empty());
+    output.join("\n")
+}
+
+fn is_heading_underline(line: &str) -> bool {
+    line.len() >= 3 && (line.chars().all(|c| c == '-') || line.chars().all(|c| c == '='))
+}
+
+fn is_list_item(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("* ") || trimmed.starts_with("- ")
+}
+
+/// Remove markdown escapes, e.g. `\--- Day 7 ---`.
+fn unescape(s: &str) -> String {
+    parse_inline(s)
+        .iter()
+        .flat_map(|word| word.iter().map(|(c, _)| *c).chain([' ']))
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Split inline markdown into styled words. Handles `*em*`, `**strong**`, `` `code` ``, links and escapes.
+fn parse_inline(text: &str) -> Vec<Word> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut words: Vec<Word> = vec![];
+    let mut word: Word = vec![];
+    let mut style = Style::default();
+    let mut open_links = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\\' if i + 1 < chars.len() && chars[i + 1].is_ascii_punctuation() => {
+                word.push((chars[i + 1], style));
+                i += 1;
+            }
+            '`' => style.italic = !style.italic,
+            '*' if !style.italic => {
+                style.bold = !style.bold;
+                // `**strong**` is rendered like `*em*`.
+                if chars.get(i + 1) == Some(&'*') {
+                    i += 1;
+                }
+            }
+            '[' if !style.italic && chars[i..].iter().collect::<String>().contains("](") => {
+                open_links += 1;
+            }
+            ']' if open_links > 0 && chars.get(i + 1) == Some(&'(') => {
+                open_links -= 1;
+                while i < chars.len() && chars[i] != ')' {
+                    i += 1;
+                }
+            }
+            c if c.is_whitespace() => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push((c, style)),
+        }
+        i += 1;
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+/// Greedily wrap styled words to `width` columns. The `prefix` is put in front of the first line,
+/// following lines are indented by its width.
+fn wrap(words: &[Word], width: usize, prefix: &str) -> Vec<String> {
+    let indent = " ".repeat(prefix.chars().count());
+    let mut lines: Vec<Vec<&Word>> = vec![vec![]];
+    let mut line_width = indent.len();
+
+    for word in words {
+        let current = lines.last_mut().unwrap();
+        if !current.is_empty() && line_width + 1 + word.len() > width {
+            lines.push(vec![word]);
+            line_width = indent.len() + word.len();
+        } else {
+            line_width += word.len() + usize::from(!current.is_empty());
+            current.push(word);
+        }
+    }
+
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, words)| {
+            let mut chars: Vec<(char, Style)> = vec![];
+            for word in words {
+                if let (Some(&(_, before)), Some(&(_, after))) = (chars.last(), word.first()) {
+                    // keep the style across the space between two words of the same span.
+                    let space = if before == after {
+                        before
+                    } else {
+                        Style::default()
+                    };
+                    chars.push((' ', space));
+                }
+                chars.extend(word.iter().copied());
+            }
+
+            let mut line = String::from(if i == 0 { prefix } else { &indent });
+            let mut style = Style::default();
+            for (c, char_style) in chars {
+                if char_style != style {
+                    if style != Style::default() {
+                        line.push_str(ANSI_RESET);
+                    }
+                    line.push_str(&char_style.codes());
+                    style = char_style;
+                }
+                line.push(c);
+            }
+
+            if style != Style::default() {
+                line.push_str(ANSI_RESET);
+            }
+            line
+        })
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_inline, render, select_part, wrap};
+    use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+    const PUZZLE: &str = "\\--- Day 7: Bridge Repair ---
+----------
+
+The engineers just need the *total calibration result*, which is the sum of the test values
+from just the equations that could possibly be true. In the above example, the sum is *`3749`*.
+
+```
+190: 10 19
+3267: 81 40 27
+```
+
+\\--- Part Two ---
+----------
+
+Now, the total calibration result is `*11387*`. See [the operators](/2024/day/7#ops).
+";
+
+    fn plain(s: &str) -> String {
+        s.replace(ANSI_BOLD, "")
+            .replace(ANSI_ITALIC, "")
+            .replace(ANSI_RESET, "")
+    }
+
+    #[test]
+    fn selects_parts() {
+        let part_one = select_part(PUZZLE, Some(1)).unwrap();
+        assert!(part_one.contains("Day 7") && !part_one.contains("Part Two"));
+
+        let part_two = select_part(PUZZLE, Some(2)).unwrap();
+        assert!(part_two.starts_with("\\--- Part Two ---"));
+
+        assert_eq!(select_part("\\--- Day 7 ---", Some(2)), None);
+        assert_eq!(select_part(PUZZLE, None).unwrap(), PUZZLE.trim_end());
+    }
+
+    #[test]
+    fn renders_headings_and_code() {
+        let rendered = render(PUZZLE, 80);
+        assert!(rendered.starts_with(&format!(
+            "{ANSI_BOLD}--- Day 7: Bridge Repair ---{ANSI_RESET}\n\n"
+        )));
+        assert!(rendered.contains(&format!("    {ANSI_ITALIC}190: 10 19{ANSI_RESET}")));
+        assert!(plain(&rendered).contains("See the operators."));
+    }
+
+    #[test]
+    fn highlights_answers() {
+        let line = wrap(&parse_inline("the sum is *`3749`*."), 80, "").join("\n");
+        assert_eq!(
+            line,
+            format!("the sum is {ANSI_BOLD}{ANSI_ITALIC}3749{ANSI_RESET}.")
+        );
+    }
+
+    #[test]
+    fn wraps_to_width() {
+        let rendered = render(PUZZLE, 40);
+        assert!(plain(&rendered).lines().all(|l| l.chars().count() <= 40));
+        assert!(plain(&rendered).contains("The engineers just need the total\ncalibration result"));
+    }
+}
@@ -3,7 +3,7 @@
 use std::{fs, io};
 
 use crate::template::timings::Timings;
-use crate::template::Day;
+use crate::template::{alloc, Day};
 
 static MARKER: &str = "<!--- benchmarking table --->";
 
@@ -21,8 +21,8 @@ impl From<std::io::Error> for Error {
 }
 
 pub struct TablePosition {
-    pos_start: usize,
-    pos_end: usize,
+    pub pos_start: usize,
+    pub pos_end: usize,
 }
 
 #[must_use]
@@ -30,8 +30,8 @@ pub fn get_path_for_bin(day: Day) -> String {
     format!("./src/bin/{day}.rs")
 }
 
-fn locate_table(readme: &str) -> Result<TablePosition, Error> {
-    let matches: Vec<_> = readme.match_indices(MARKER).collect();
+pub fn locate_table(readme: &str, marker: &str) -> Result<TablePosition, Error> {
+    let matches: Vec<_> = readme.match_indices(marker).collect();
 
     if matches.len() > 2 {
         return Err(Error::Parser(
@@ -59,18 +59,22 @@ fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String
         MARKER.into(),
         header,
         String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
+        "| Day | Part 1 | Part 2 | Total | Memory |".into(),
+        "| :---: | :---: | :---:  | :---: | :---: |".into(),
     ];
 
     for timing in timings.data {
         let path = get_path_for_bin(timing.day);
         lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
+            "| [Day {}]({}) | `{}` | `{}` | `{:.2}ms` | `{}` |",
             timing.day.into_inner(),
             path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
+            timing.part_1.map_or_else(|| "-".into(), |p| p.to_string()),
+            timing.part_2.map_or_else(|| "-".into(), |p| p.to_string()),
+            timing.total_nanos / 1_000_000_f64,
+            timing
+                .peak_bytes
+                .map_or_else(|| "-".into(), alloc::format_bytes),
         ));
     }
 
@@ -81,8 +85,8 @@ fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String
     lines.join("\n")
 }
 
-fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result<(), Error> {
-    let positions = locate_table(s)?;
+pub fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result<(), Error> {
+    let positions = locate_table(s, MARKER)?;
     let table = construct_table("##", timings, total_millis);
     s.replace_range(positions.pos_start..positions.pos_end, &table);
     Ok(())
@@ -100,28 +104,52 @@ pub fn update(timings: Timings) -> Result<(), Error> {
 #[cfg(feature = "test_lib")]
 mod tests {
     use super::{update_content, MARKER};
-    use crate::{day, template::timings::Timing, template::timings::Timings};
+    use crate::{
+        day,
+        template::timings::{PartTiming, Timing, Timings},
+    };
 
     fn get_mock_timings() -> Timings {
         Timings {
             data: vec![
                 Timing {
                     day: day!(1),
-                    part_1: Some("10ms".into()),
-                    part_2: Some("20ms".into()),
-                    total_nanos: 3e+10,
+                    part_1: Some(PartTiming {
+                        nanos: 10e+6,
+                        samples: Some(10),
+                    }),
+                    part_2: Some(PartTiming {
+                        nanos: 20e+6,
+                        samples: Some(10),
+                    }),
+                    total_nanos: 3e+7,
+                    peak_bytes: Some(2048),
                 },
                 Timing {
                     day: day!(2),
-                    part_1: Some("30ms".into()),
-                    part_2: Some("40ms".into()),
-                    total_nanos: 7e+10,
+                    part_1: Some(PartTiming {
+                        nanos: 30e+6,
+                        samples: Some(10),
+                    }),
+                    part_2: Some(PartTiming {
+                        nanos: 40e+6,
+                        samples: Some(10),
+                    }),
+                    total_nanos: 7e+7,
+                    peak_bytes: None,
                 },
                 Timing {
                     day: day!(4),
-                    part_1: Some("40ms".into()),
-                    part_2: Some("50ms".into()),
-                    total_nanos: 9e+10,
+                    part_1: Some(PartTiming {
+                        nanos: 40e+6,
+                        samples: Some(10),
+                    }),
+                    part_2: Some(PartTiming {
+                        nanos: 50e+6,
+                        samples: Some(10),
+                    }),
+                    total_nanos: 9e+7,
+                    peak_bytes: None,
                 },
             ],
         }
@@ -167,11 +195,11 @@ mod tests {
             "<!--- benchmarking table --->",
             "## Benchmarks",
             "",
-            "| Day | Part 1 | Part 2 |",
-            "| :---: | :---: | :---:  |",
-            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` |",
-            "| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` |",
-            "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` |",
+            "| Day | Part 1 | Part 2 | Total | Memory |",
+            "| :---: | :---: | :---:  | :---: | :---: |",
+            "| [Day 1](./src/bin/01.rs) | `10.0ms` | `20.0ms` | `30.00ms` | `2.0 KiB` |",
+            "| [Day 2](./src/bin/02.rs) | `30.0ms` | `40.0ms` | `70.00ms` | `-` |",
+            "| [Day 4](./src/bin/04.rs) | `40.0ms` | `50.0ms` | `90.00ms` | `-` |",
             "",
             "**Total: 190.00ms**",
             "<!--- benchmarking table --->",
@@ -0,0 +1,128 @@
+/// Module that updates the readme with star progress, based on the accepted answers in `data/answers.json`.
+/// This replaces the table that the `advent-readme-stars` action would otherwise render.
+use std::path::Path;
+
+use crate::template::answers::Answers;
+use crate::template::readme_benchmarks::{get_path_for_bin, locate_table, Error};
+use crate::template::{all_days, Day};
+
+static MARKER: &str = "<!--- advent_readme_stars table --->";
+
+const STAR: &str = "⭐";
+const MAX_STARS: u32 = 50;
+
+#[must_use]
+pub fn get_puzzle_url(day: Day, year: u16) -> String {
+    format!("https://adventofcode.com/{year}/day/{}", day.into_inner())
+}
+
+/// Days that are listed in the table: days with a solution file or at least one accepted answer.
+pub fn listed_days(answers: &Answers) -> Vec<Day> {
+    all_days()
+        .filter(|&day| answers.get(day).is_some() || Path::new(&get_path_for_bin(day)).exists())
+        .collect()
+}
+
+fn construct_table(prefix: &str, answers: &Answers, days: &[Day], year: Option<u16>) -> String {
+    let header = match year {
+        Some(year) => format!("{prefix} {year} Results"),
+        None => format!("{prefix} Results"),
+    };
+
+    let mut lines: Vec<String> = vec![
+        MARKER.into(),
+        header,
+        String::new(),
+        format!("**Progress: {}/{MAX_STARS} {STAR}**", answers.stars()),
+        String::new(),
+        "| Day | Part 1 | Part 2 | Solution |".into(),
+        "| :---: | :---: | :---: | :---: |".into(),
+    ];
+
+    for &day in days {
+        let label = match year {
+            Some(year) => format!("[Day {}]({})", day.into_inner(), get_puzzle_url(day, year)),
+            None => format!("Day {}", day.into_inner()),
+        };
+
+        let answer = answers.get(day);
+        let star = |part: Option<&String>| if part.is_some() { STAR } else { " " };
+
+        lines.push(format!(
+            "| {label} | {} | {} | [{day}.rs]({}) |",
+            star(answer.and_then(|a| a.part_1.as_ref())),
+            star(answer.and_then(|a| a.part_2.as_ref())),
+            get_path_for_bin(day),
+        ));
+    }
+
+    lines.push(MARKER.into());
+
+    lines.join("\n")
+}
+
+pub fn update_content(
+    s: &mut String,
+    answers: &Answers,
+    days: &[Day],
+    year: Option<u16>,
+) -> Result<(), Error> {
+    let positions = locate_table(s, MARKER)?;
+    let table = construct_table("##", answers, days, year);
+    s.replace_range(positions.pos_start..positions.pos_end, &table);
+    Ok(())
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{update_content, MARKER};
+    use crate::{day, template::answers::Answers};
+
+    fn get_mock_answers() -> Answers {
+        Answers::default()
+            .record(day!(1), 1, "11")
+            .record(day!(1), 2, "31")
+            .record(day!(2), 1, "2")
+    }
+
+    #[test]
+    #[should_panic]
+    fn errors_if_marker_not_present() {
+        let mut s = "# readme".to_string();
+        update_content(&mut s, &get_mock_answers(), &[day!(1)], Some(2024)).unwrap();
+    }
+
+    #[test]
+    fn updates_existing_stars() {
+        let mut s = format!("foo\n{}{}\nbaz", MARKER, MARKER);
+        let days = [day!(1), day!(2)];
+        update_content(&mut s, &get_mock_answers(), &days, Some(2024)).unwrap();
+        update_content(&mut s, &get_mock_answers(), &days, Some(2024)).unwrap();
+        assert_eq!(s.matches(MARKER).count(), 2);
+        assert_eq!(s.matches("## 2024 Results").count(), 1);
+    }
+
+    #[test]
+    fn format_stars() {
+        let mut s = format!("foo\n{}\n{}\nbaz", MARKER, MARKER);
+        let days = [day!(1), day!(2), day!(3)];
+        update_content(&mut s, &get_mock_answers(), &days, Some(2024)).unwrap();
+        let expected = [
+            "foo",
+            "<!--- advent_readme_stars table --->",
+            "## 2024 Results",
+            "",
+            "**Progress: 3/50 ⭐**",
+            "",
+            "| Day | Part 1 | Part 2 | Solution |",
+            "| :---: | :---: | :---: | :---: |",
+            "| [Day 1](https://adventofcode.com/2024/day/1) | ⭐ | ⭐ | [01.rs](./src/bin/01.rs) |",
+            "| [Day 2](https://adventofcode.com/2024/day/2) | ⭐ |   | [02.rs](./src/bin/02.rs) |",
+            "| [Day 3](https://adventofcode.com/2024/day/3) |   |   | [03.rs](./src/bin/03.rs) |",
+            "<!--- advent_readme_stars table --->",
+            "baz",
+        ]
+        .join("\n");
+        assert_eq!(s, expected);
+    }
+}
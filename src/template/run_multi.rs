@@ -68,6 +68,7 @@ pub fn get_path_for_bin(day: Day) -> String {
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
     use super::{get_path_for_bin, Error};
+    use crate::template::timings::{parse_duration, PartTiming};
     use crate::template::Day;
     use std::{
         io::{BufRead, BufReader},
@@ -78,6 +79,17 @@ pub mod child_commands {
 
     /// Run the solution bin for a given day
     pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+        // mirror `--time` flag to child invocations.
+        let child_args: &[&str] = if is_timed { &["--time"] } else { &[] };
+        run_solution_with_args(day, is_release, child_args)
+    }
+
+    /// Run the solution bin for a given day, forwarding `child_args` to the binary.
+    pub fn run_solution_with_args(
+        day: Day,
+        is_release: bool,
+        child_args: &[&str],
+    ) -> Result<Vec<String>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
             return Ok(vec![]);
@@ -90,10 +102,9 @@ pub mod child_commands {
             args.push("--release");
         }
 
-        if is_timed {
-            // mirror `--time` flag to child invocations.
+        if !child_args.is_empty() {
             args.push("--");
-            args.push("--time");
+            args.extend(child_args);
         }
 
         // spawn child command with piped stdout/stderr.
@@ -118,7 +129,9 @@ pub mod child_commands {
 
         for line in stdout.lines() {
             let line = line.unwrap();
-            println!("{line}");
+            if !line.starts_with(TIMING_PREFIX) {
+                println!("{line}");
+            }
             output.push(line);
         }
 
@@ -128,65 +141,97 @@ pub mod child_commands {
         Ok(output)
     }
 
+    /// Prefix of the machine-readable line a benched solution part prints next to its human-readable result,
+    /// e.g. `::timing:: part=1 nanos=74130 samples=100 peak_bytes=512`. These lines are not echoed.
+    pub const TIMING_PREFIX: &str = "::timing::";
+
+    #[must_use]
+    pub fn format_timing_line(
+        part: u8,
+        nanos: u128,
+        samples: u128,
+        peak_bytes: Option<u64>,
+    ) -> String {
+        let memory = peak_bytes
+            .map(|bytes| format!(" peak_bytes={bytes}"))
+            .unwrap_or_default();
+        format!("{TIMING_PREFIX} part={part} nanos={nanos} samples={samples}{memory}")
+    }
+
+    /// Parse a line written by [`format_timing_line`] into the part, its timing and its peak heap usage.
+    pub fn parse_timing_line(line: &str) -> Option<(u8, PartTiming, Option<u64>)> {
+        let fields = line.strip_prefix(TIMING_PREFIX)?;
+
+        let (mut part, mut nanos, mut samples, mut peak_bytes) = (None, None, None, None);
+
+        for field in fields.split_whitespace() {
+            match field.split_once('=')? {
+                ("part", value) => part = value.parse().ok(),
+                ("nanos", value) => nanos = value.parse().ok(),
+                ("samples", value) => samples = value.parse().ok(),
+                ("peak_bytes", value) => peak_bytes = value.parse().ok(),
+                _ => {}
+            }
+        }
+
+        Some((
+            part?,
+            PartTiming {
+                nanos: nanos?,
+                samples,
+            },
+            peak_bytes,
+        ))
+    }
+
+    /// Whether a line of human-readable solution output reports a benched timing.
+    pub fn is_timing_line(line: &str) -> bool {
+        line.contains(" samples)") || line.contains(" samples, ")
+    }
+
     pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
         let mut timings = super::Timing {
             day,
             part_1: None,
             part_2: None,
             total_nanos: 0_f64,
+            peak_bytes: None,
         };
 
-        output
-            .iter()
-            .filter_map(|l| {
-                if !l.contains(" samples)") {
-                    return None;
-                }
-
-                let Some((timing_str, nanos)) = parse_time(l) else {
-                    eprintln!("Could not parse timings from line: {l}");
-                    return None;
-                };
-
-                let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
-            })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
-                } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
-                }
-
-                timings.total_nanos += nanos;
-            });
+        for line in output.iter().filter(|l| l.starts_with(TIMING_PREFIX)) {
+            let Some((part, timing, peak_bytes)) = parse_timing_line(line) else {
+                eprintln!("Could not parse timings from line: {line}");
+                continue;
+            };
 
-        timings
-    }
+            match part {
+                1 => timings.part_1 = Some(timing),
+                2 => timings.part_2 = Some(timing),
+                _ => continue,
+            }
+
+            timings.total_nanos += timing.nanos;
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
+            if let Some(bytes) = peak_bytes {
+                timings.peak_bytes = Some(timings.peak_bytes.map_or(bytes, |b| b.max(bytes)));
+            }
+        }
+
+        timings
     }
 
-    fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
+    /// Parse the timing of a human-readable line, e.g. `Part 1: 42 (74.13ns @ 100 samples)`.
+    pub fn parse_time(line: &str) -> Option<(&str, f64)> {
         let str_timing = line
-            .split(" samples)")
+            .split(" samples")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
 
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
-
-        Some((str_timing, parsed_timing))
+        Some((str_timing, parse_duration(str_timing)?))
     }
 
     /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
@@ -205,38 +250,63 @@ pub mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{format_timing_line, parse_exec_time, parse_time};
 
-        use crate::day;
+        use crate::{day, template::timings::PartTiming};
 
         #[test]
         fn parses_execution_times() {
             let res = parse_exec_time(
                 &[
-                    "Part 1: 0 (74.13ns @ 100000 samples)".into(),
-                    "Part 2: 10 (74.13ms @ 99999 samples)".into(),
+                    "Part 1: 0 (74.0ns @ 100000 samples)".into(),
+                    format_timing_line(1, 74, 100000, None),
+                    "Part 2: 10 (74.1ms @ 99999 samples)".into(),
+                    format_timing_line(2, 74_130_000, 99999, None),
+                    "".into(),
+                ],
+                day!(1),
+            );
+            assert_approx_eq!(res.total_nanos, 74130074_f64);
+            assert_eq!(
+                res.part_1,
+                Some(PartTiming {
+                    nanos: 74_f64,
+                    samples: Some(100000)
+                })
+            );
+            assert_eq!(res.part_2.unwrap().to_string(), "74.1ms");
+            assert_eq!(res.peak_bytes, None);
+        }
+
+        #[test]
+        fn parses_peak_memory() {
+            let res = parse_exec_time(
+                &[
+                    format_timing_line(1, 74, 100000, Some(512)),
+                    format_timing_line(2, 74_130_000, 99999, Some(1536)),
                     "".into(),
                 ],
                 day!(1),
             );
-            assert_approx_eq!(res.total_nanos, 74130074.13_f64);
-            assert_eq!(res.part_1.unwrap(), "74.13ns");
-            assert_eq!(res.part_2.unwrap(), "74.13ms");
+            assert_approx_eq!(res.total_nanos, 74130074_f64);
+            assert_eq!(res.peak_bytes, Some(1536));
         }
 
         #[test]
-        fn parses_with_patterns_in_input() {
+        fn ignores_human_readable_lines() {
             let res = parse_exec_time(
                 &[
-                    "Part 1: @ @ @ ( ) ms (2s @ 5 samples)".into(),
-                    "Part 2: 10s (100ms @ 1 samples)".into(),
+                    "Part 1: @ @ @ ( ) ms (2.0s @ 5 samples)".into(),
+                    format_timing_line(1, 2_000_000_000, 5, None),
+                    "Part 2: ::timing:: part=2 (100.0ms @ 1 samples)".into(),
+                    format_timing_line(2, 100_000_000, 1, None),
                     "".into(),
                 ],
                 day!(1),
             );
             assert_approx_eq!(res.total_nanos, 2100000000_f64);
-            assert_eq!(res.part_1.unwrap(), "2s");
-            assert_eq!(res.part_2.unwrap(), "100ms");
+            assert_eq!(res.part_1.unwrap().to_string(), "2.0s");
+            assert_eq!(res.part_2.unwrap().to_string(), "100.0ms");
         }
 
         #[test]
@@ -253,5 +323,12 @@ pub mod child_commands {
             assert_eq!(res.part_1.is_none(), true);
             assert_eq!(res.part_2.is_none(), true);
         }
+
+        #[test]
+        fn parses_human_readable_times() {
+            let (s, nanos) = parse_time("Part 1 [n=120]: @ ( ) ms (2s @ 5 samples)").unwrap();
+            assert_eq!(s, "2s");
+            assert_approx_eq!(nanos, 2000000000_f64);
+        }
     }
 }
@@ -2,34 +2,242 @@
 use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{stdout, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
 use std::process::Output;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
-use std::{cmp, env, process};
+use std::{cmp, env, fs, process};
 
+use crate::template::answers::Answers;
+use crate::template::meta::Meta;
+use crate::template::run_multi::child_commands;
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{alloc, aoc_cli, profiling, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::visualize::{self, image};
 
 pub fn run_part<I: Copy, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
+    let (result, duration, samples, peak_bytes) =
         run_timed(func, input, |result| print_result(result, &part_str, ""));
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    print_result(
+        &result,
+        &part_str,
+        &format_duration(&duration, samples, peak_bytes),
+    );
+
+    // `cargo time` reads the exact numbers from this line instead of the formatted result.
+    if result.is_some() && env::args().any(|x| x == "--time") {
+        println!(
+            "{}",
+            child_commands::format_timing_line(part, duration.as_nanos(), samples, peak_bytes)
+        );
+    }
 
     if let Some(result) = result {
         submit_result(result, day, part);
     }
 }
 
+static TRACING: AtomicBool = AtomicBool::new(false);
+
+/// Whether the part is run by `cargo solve <day> --trace`, so a solution can print how it got its result.
+pub fn is_tracing() -> bool {
+    TRACING.load(Ordering::Relaxed)
+}
+
+/// Run a solution part once without timing it, with [`is_tracing`] enabled.
+/// Used by `cargo solve <day> --trace`.
+pub fn run_part_traced<I: Copy, T: Display>(
+    func: impl Fn(I) -> Option<T>,
+    input: I,
+    _day: Day,
+    part: u8,
+) {
+    let part_str = format!("Part {part}");
+    println!("{part_str}:");
+
+    TRACING.store(true, Ordering::Relaxed);
+    let result = func(input);
+    TRACING.store(false, Ordering::Relaxed);
+
+    match result {
+        Some(result) => println!("{part_str}: {ANSI_BOLD}{result}{ANSI_RESET}"),
+        None => println!("{part_str}: ✖"),
+    }
+}
+
+/// Run a solution part once, then play back the frames it recorded or export them as images.
+/// Used by `cargo solve <day> --visualize [--export <format>]`.
+pub fn run_part_visualized<I: Copy, T: Display>(
+    func: impl Fn(I) -> Option<T>,
+    input: I,
+    day: Day,
+    part: u8,
+) {
+    let part_str = format!("Part {part}");
+
+    if !visualize::ENABLED {
+        eprintln!("{part_str}: visualizing requires the `visualize` feature. Use `cargo solve <day> --visualize`.");
+        process::exit(1);
+    }
+
+    drop(visualize::take_frames());
+    let result = func(input);
+    let frames = visualize::take_frames();
+
+    match result {
+        Some(result) => println!("{part_str}: {ANSI_BOLD}{result}{ANSI_RESET}"),
+        None => println!("{part_str}: ✖"),
+    }
+
+    let export = image::Options::parse(&mut pico_args::Arguments::from_env());
+    match export {
+        _ if frames.is_empty() => {
+            println!("{part_str}: {ANSI_ITALIC}no frames recorded{ANSI_RESET}")
+        }
+        Ok(Some(options)) => match image::export(&frames, day, part, &options) {
+            Ok(path) => println!("{part_str}: wrote {} frames to \"{path}\"", frames.len()),
+            Err(e) => eprintln!("{part_str}: failed to export frames: {e}"),
+        },
+        Ok(None) => {
+            if let Err(e) = visualize::play(&frames, &format!("Day {day} {part_str}")) {
+                eprintln!("{part_str}: failed to play frames: {e}");
+            }
+        }
+        Err(e) => eprintln!("{part_str}: invalid export options: {e}"),
+    }
+}
+
+/// Run a solution part against inputs of increasing size, benching each one.
+/// Used by `cargo time <day> --scale` to chart runtime against input size.
+pub fn run_part_scaled<T: Display>(
+    func: impl Fn(&str) -> Option<T>,
+    input: &str,
+    day: Day,
+    part: u8,
+) {
+    // a truncated input may not be a valid puzzle, so failures are reported instead of aborting.
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    for scaled_input in scale_inputs(day, input) {
+        let part_str = format!("Part {part} [n={}]", scaled_input.len());
+        let input = scaled_input.as_str();
+
+        let timer = Instant::now();
+        let Ok(result) = panic::catch_unwind(AssertUnwindSafe(|| func(input))) else {
+            println!("{part_str}: ✖ (panicked)");
+            continue;
+        };
+        let base_time = timer.elapsed();
+
+        print_result(&result, &part_str, "");
+        let (duration, samples) = bench(&func, input, &base_time);
+        print_result(
+            &result,
+            &part_str,
+            &format_duration(&duration, samples, None),
+        );
+    }
+
+    panic::set_hook(default_hook);
+}
+
+/// Run a solution part in a loop under a sampling profiler and write a flamegraph of it.
+/// Used by `cargo solve <day> --profile`.
+pub fn run_part_profiled<I: Copy, T: Display>(
+    func: impl Fn(I) -> Option<T>,
+    input: I,
+    day: Day,
+    part: u8,
+) {
+    let part_str = format!("Part {part}");
+
+    print!(
+        "{part_str}: > {ANSI_ITALIC}profiling for {:?}{ANSI_RESET}",
+        profiling::PROFILE_BUDGET
+    );
+    let _ = stdout().flush();
+
+    let result = profiling::profile(
+        || {
+            black_box(func(black_box(input)));
+        },
+        day,
+        part,
+    );
+
+    print!("\r");
+    match result {
+        Ok(profile) => {
+            println!(
+                "{part_str}: {ANSI_BOLD}{} samples{ANSI_RESET} over {} iterations",
+                profile.samples, profile.iterations
+            );
+            println!(
+                "  wrote \"{}\" and \"{}\"",
+                profile.folded_path, profile.svg_path
+            );
+        }
+        Err(profiling::Error::FeatureDisabled) => {
+            eprintln!("{part_str}: profiling requires the `profile` feature. Use `cargo solve <day> --profile`.");
+            process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("{part_str}: failed to profile: {e:?}");
+            process::exit(1);
+        }
+    }
+}
+
+/// Inputs used for scaling benchmarks, ordered by size.
+/// Generated inputs in `data/scale/<day>/` take precedence, otherwise the real input is truncated by lines.
+fn scale_inputs(day: Day, input: &str) -> Vec<String> {
+    let generated_dir = Path::new("data").join("scale").join(day.to_string());
+
+    if let Ok(entries) = fs::read_dir(generated_dir) {
+        let mut generated: Vec<String> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "txt"))
+            .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+            .collect();
+
+        if !generated.is_empty() {
+            generated.sort_by_key(String::len);
+            return generated;
+        }
+    }
+
+    let lines: Vec<&str> = input.lines().collect();
+    let mut line_counts: Vec<usize> = SCALE_FRACTIONS
+        .iter()
+        .map(|fraction| cmp::max(1, lines.len() / fraction))
+        .collect();
+    line_counts.dedup();
+
+    line_counts
+        .into_iter()
+        .map(|count| lines[..count].join("\n") + "\n")
+        .collect()
+}
+
+/// Divisors applied to the line count of the real input when no generated inputs exist.
+const SCALE_FRACTIONS: [usize; 7] = [64, 32, 16, 8, 4, 2, 1];
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+///
+/// The peak heap usage of the first execution is measured, unless DHAT is tracking allocations instead.
 fn run_timed<I: Copy, T>(
     func: impl Fn(I) -> T,
     input: I,
     hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+) -> (T, Duration, u128, Option<u64>) {
+    let baseline = alloc::reset_peak();
     let timer = Instant::now();
     let result = {
         #[cfg(feature = "dhat-heap")]
@@ -38,6 +246,11 @@ fn run_timed<I: Copy, T>(
         func(input)
     };
     let base_time = timer.elapsed();
+    let peak_bytes = if cfg!(feature = "dhat-heap") {
+        None
+    } else {
+        Some((alloc::peak() - baseline) as u64)
+    };
 
     hook(&result);
 
@@ -47,7 +260,7 @@ fn run_timed<I: Copy, T>(
         (base_time, 1)
     };
 
-    (result, run.0, run.1)
+    (result, run.0, run.1, peak_bytes)
 }
 
 fn bench<I: Copy, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
@@ -82,11 +295,15 @@ fn average_duration(numbers: &[Duration]) -> u128 {
         / numbers.len() as u128
 }
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
+fn format_duration(duration: &Duration, samples: u128, peak_bytes: Option<u64>) -> String {
+    let memory = peak_bytes
+        .map(|bytes| format!(", {}", alloc::format_bytes(bytes)))
+        .unwrap_or_default();
+
     if samples == 1 {
-        format!(" ({duration:.1?})")
+        format!(" ({duration:.1?}{memory})")
     } else {
-        format!(" ({duration:.1?} @ {samples} samples)")
+        format!(" ({duration:.1?} @ {samples} samples{memory})")
     }
 }
 
@@ -161,5 +378,25 @@ fn submit_result<T: Display>(
     }
 
     println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    let answer = result.to_string();
+    let output = aoc_cli::submit(day, part, &answer);
+
+    if let Ok(output) = &output {
+        let is_correct = aoc_cli::is_correct_answer(output);
+
+        if is_correct {
+            let answers = Answers::read_from_file().record(day, part, &answer);
+            if let Err(e) = answers.store_file() {
+                eprintln!("Failed to store answer: {e}");
+            }
+        }
+
+        let mut meta = Meta::read_from_file(day);
+        meta.record_submission(part, is_correct);
+        if let Err(e) = meta.store_file() {
+            eprintln!("Failed to store submission: {e}");
+        }
+    }
+
+    Some(output)
 }
@@ -0,0 +1,411 @@
+/// Module that charts the runtime of a solution against the size of its input.
+/// Measurements come from running a solution binary with `--scale`, see `runner::run_part_scaled`.
+use std::{fmt::Write, fs, io, path::Path};
+
+use crate::template::run_multi::child_commands;
+use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum Error {
+    NotSolved,
+    NotEnoughSamples,
+    Run(super::run_multi::Error),
+    IO(io::Error),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+/// A single measurement of a solution part at a given input size (in bytes).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sample {
+    pub size: usize,
+    pub nanos: f64,
+}
+
+/// Measurements for a single solution part, ordered by input size.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Series {
+    pub part: u8,
+    pub samples: Vec<Sample>,
+}
+
+/// Least-squares fit of `nanos = c * size^exponent` on a log-log scale.
+#[derive(Clone, Copy, Debug)]
+pub struct Fit {
+    pub exponent: f64,
+    pub r_squared: f64,
+}
+
+#[must_use]
+pub fn get_path_for_output(day: Day, extension: &str) -> String {
+    format!("data/scale/{day}.{extension}")
+}
+
+/// Bench `day` against inputs of increasing size and report the empirical complexity of each part.
+/// Writes a CSV of all samples and an SVG chart next to the generated inputs in `data/scale`.
+pub fn run(day: Day) -> Result<(), Error> {
+    println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+    println!("------");
+
+    let output =
+        child_commands::run_solution_with_args(day, true, &["--scale"]).map_err(Error::Run)?;
+
+    if output.is_empty() {
+        return Err(Error::NotSolved);
+    }
+
+    let series = parse_scaled_output(&output);
+
+    if series.iter().all(|s| s.samples.len() < 2) {
+        return Err(Error::NotEnoughSamples);
+    }
+
+    println!();
+    print!("{}", render_ascii(&series, 60, 16));
+    println!();
+
+    for s in &series {
+        match fit(&s.samples) {
+            Some(f) => println!(
+                "{ANSI_BOLD}Part {}:{ANSI_RESET} O(n^{:.2}) {ANSI_ITALIC}(r² = {:.3}){ANSI_RESET}",
+                s.part, f.exponent, f.r_squared
+            ),
+            None => println!("{ANSI_BOLD}Part {}:{ANSI_RESET} not enough samples", s.part),
+        }
+    }
+
+    fs::create_dir_all(Path::new("data").join("scale"))?;
+
+    let csv_path = get_path_for_output(day, "csv");
+    let svg_path = get_path_for_output(day, "svg");
+    fs::write(&csv_path, to_csv(&series))?;
+    fs::write(&svg_path, render_svg(day, &series))?;
+
+    println!();
+    println!("Wrote samples to \"{csv_path}\" and chart to \"{svg_path}\".");
+    Ok(())
+}
+
+/// Collect the `Part <n> [n=<size>]: <result> (<time> @ <samples> samples)` lines of a scaled run.
+pub fn parse_scaled_output(output: &[String]) -> Vec<Series> {
+    let mut series: Vec<Series> = vec![];
+
+    for line in output.iter().filter(|l| child_commands::is_timing_line(l)) {
+        let Some((part, size)) = parse_label(line) else {
+            continue;
+        };
+
+        let Some((_, nanos)) = child_commands::parse_time(line) else {
+            eprintln!("Could not parse timings from line: {line}");
+            continue;
+        };
+
+        let sample = Sample { size, nanos };
+        match series.iter_mut().find(|s| s.part == part) {
+            Some(s) => s.samples.push(sample),
+            None => series.push(Series {
+                part,
+                samples: vec![sample],
+            }),
+        }
+    }
+
+    for s in &mut series {
+        s.samples.sort_by_key(|sample| sample.size);
+    }
+    series.sort_by_key(|s| s.part);
+    series
+}
+
+fn parse_label(line: &str) -> Option<(u8, usize)> {
+    let label = line.split(':').next()?.strip_prefix("Part ")?;
+    let (part, size) = label.split_once(" [n=")?;
+    Some((part.parse().ok()?, size.strip_suffix(']')?.parse().ok()?))
+}
+
+/// Fit the exponent `k` of `nanos ~ size^k` with a least-squares regression in log-log space.
+/// Returns `None` if there are fewer than two distinct input sizes.
+pub fn fit(samples: &[Sample]) -> Option<Fit> {
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .filter(|s| s.size > 0 && s.nanos > 0.0)
+        .map(|s| ((s.size as f64).ln(), s.nanos.ln()))
+        .collect();
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+
+    let ss_xx: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    let ss_xy: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    let ss_yy: f64 = points.iter().map(|p| (p.1 - mean_y).powi(2)).sum();
+
+    if points.len() < 2 || ss_xx == 0.0 {
+        return None;
+    }
+
+    let exponent = ss_xy / ss_xx;
+    let r_squared = if ss_yy == 0.0 {
+        1.0
+    } else {
+        (ss_xy * ss_xy) / (ss_xx * ss_yy)
+    };
+
+    Some(Fit {
+        exponent,
+        r_squared,
+    })
+}
+
+pub fn to_csv(series: &[Series]) -> String {
+    let mut csv = String::from("part,size_bytes,nanos\n");
+    for s in series {
+        for sample in &s.samples {
+            let _ = writeln!(csv, "{},{},{}", s.part, sample.size, sample.nanos);
+        }
+    }
+    csv
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Bounds of all samples on a log-log scale, as `(min_x, max_x, min_y, max_y)`.
+fn log_bounds(series: &[Series]) -> Option<(f64, f64, f64, f64)> {
+    let samples = || series.iter().flat_map(|s| s.samples.iter());
+    let xs = samples().map(|s| (s.size.max(1) as f64).log10());
+    let ys = samples().map(|s| s.nanos.max(1.0).log10());
+
+    let (min_x, max_x) = xs.fold(None, min_max)?;
+    let (min_y, max_y) = ys.fold(None, min_max)?;
+
+    // avoid a degenerate axis when all samples share a value.
+    Some((
+        min_x,
+        max_x.max(min_x + 1e-9),
+        min_y,
+        max_y.max(min_y + 1e-9),
+    ))
+}
+
+fn min_max(acc: Option<(f64, f64)>, v: f64) -> Option<(f64, f64)> {
+    Some(acc.map_or((v, v), |(lo, hi)| (lo.min(v), hi.max(v))))
+}
+
+/// Render a log-log scatter plot of time against input size. Samples of each part are drawn with their part number.
+pub fn render_ascii(series: &[Series], width: usize, height: usize) -> String {
+    let Some((min_x, max_x, min_y, max_y)) = log_bounds(series) else {
+        return String::new();
+    };
+
+    let mut canvas = vec![vec![' '; width]; height];
+
+    for s in series {
+        let marker = char::from_digit(u32::from(s.part), 10).unwrap_or('*');
+        for sample in &s.samples {
+            let x = ((sample.size.max(1) as f64).log10() - min_x) / (max_x - min_x);
+            let y = (sample.nanos.max(1.0).log10() - min_y) / (max_y - min_y);
+            let col = (x * (width - 1) as f64).round() as usize;
+            let row = height - 1 - (y * (height - 1) as f64).round() as usize;
+            canvas[row][col] = marker;
+        }
+    }
+
+    let y_labels = [
+        format_nanos(10_f64.powf(max_y)),
+        format_nanos(10_f64.powf(min_y)),
+    ];
+    let label_width = y_labels.iter().map(String::len).max().unwrap_or(0);
+
+    let mut out = String::new();
+    for (i, row) in canvas.iter().enumerate() {
+        let label = match i {
+            0 => y_labels[0].as_str(),
+            i if i == height - 1 => y_labels[1].as_str(),
+            _ => "",
+        };
+        let _ = writeln!(
+            out,
+            "{label:>label_width$} │{}",
+            row.iter().collect::<String>()
+        );
+    }
+
+    let min_label = format!("{:.0}B", 10_f64.powf(min_x));
+    let max_label = format!("{:.0}B", 10_f64.powf(max_x));
+    let _ = writeln!(out, "{:>label_width$} └{}", "", "─".repeat(width));
+    let _ = writeln!(
+        out,
+        "{:>label_width$}  {min_label}{max_label:>pad$}",
+        "",
+        pad = width.saturating_sub(min_label.len())
+    );
+    out
+}
+
+const SVG_WIDTH: f64 = 640.0;
+const SVG_HEIGHT: f64 = 400.0;
+const SVG_MARGIN: f64 = 60.0;
+const SVG_COLOURS: [&str; 2] = ["#d62728", "#1f77b4"];
+
+/// Render a self-contained SVG chart of time against input size on log-log axes.
+pub fn render_svg(day: Day, series: &[Series]) -> String {
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{SVG_WIDTH}\" height=\"{SVG_HEIGHT}\" font-family=\"monospace\" font-size=\"12\">\n"
+    );
+    let _ = writeln!(
+        svg,
+        "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n<text x=\"{}\" y=\"24\" text-anchor=\"middle\" font-size=\"16\">Day {day}: time vs input size</text>",
+        SVG_WIDTH / 2.0
+    );
+
+    let Some((min_x, max_x, min_y, max_y)) = log_bounds(series) else {
+        svg.push_str("</svg>\n");
+        return svg;
+    };
+
+    let plot_w = SVG_WIDTH - 2.0 * SVG_MARGIN;
+    let plot_h = SVG_HEIGHT - 2.0 * SVG_MARGIN;
+    let project = |sample: &Sample| {
+        let x = ((sample.size.max(1) as f64).log10() - min_x) / (max_x - min_x);
+        let y = (sample.nanos.max(1.0).log10() - min_y) / (max_y - min_y);
+        (
+            SVG_MARGIN + x * plot_w,
+            SVG_HEIGHT - SVG_MARGIN - y * plot_h,
+        )
+    };
+
+    let (left, right) = (SVG_MARGIN, SVG_WIDTH - SVG_MARGIN);
+    let (top, bottom) = (SVG_MARGIN, SVG_HEIGHT - SVG_MARGIN);
+    let _ = writeln!(
+        svg,
+        "<polyline points=\"{left},{top} {left},{bottom} {right},{bottom}\" fill=\"none\" stroke=\"black\"/>"
+    );
+    let _ = writeln!(
+        svg,
+        "<text x=\"{left}\" y=\"{}\" text-anchor=\"middle\">{:.0}B</text>\n<text x=\"{right}\" y=\"{}\" text-anchor=\"middle\">{:.0}B</text>",
+        bottom + 18.0,
+        10_f64.powf(min_x),
+        bottom + 18.0,
+        10_f64.powf(max_x),
+    );
+    let _ = writeln!(
+        svg,
+        "<text x=\"{}\" y=\"{bottom}\" text-anchor=\"end\">{}</text>\n<text x=\"{}\" y=\"{top}\" text-anchor=\"end\">{}</text>",
+        left - 6.0,
+        format_nanos(10_f64.powf(min_y)),
+        left - 6.0,
+        format_nanos(10_f64.powf(max_y)),
+    );
+
+    for (i, s) in series.iter().enumerate() {
+        let colour = SVG_COLOURS[i % SVG_COLOURS.len()];
+        let points: Vec<String> = s
+            .samples
+            .iter()
+            .map(project)
+            .map(|(x, y)| format!("{x:.1},{y:.1}"))
+            .collect();
+
+        let _ = writeln!(
+            svg,
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{colour}\" stroke-width=\"2\"/>",
+            points.join(" ")
+        );
+        for (x, y) in s.samples.iter().map(project) {
+            let _ = writeln!(
+                svg,
+                "<circle cx=\"{x:.1}\" cy=\"{y:.1}\" r=\"3\" fill=\"{colour}\"/>"
+            );
+        }
+
+        let legend = match fit(&s.samples) {
+            Some(f) => format!("Part {}: O(n^{:.2})", s.part, f.exponent),
+            None => format!("Part {}", s.part),
+        };
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" fill=\"{colour}\">{legend}</text>",
+            left + 10.0,
+            top + 16.0 * (i as f64 + 1.0)
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn format_nanos(nanos: f64) -> String {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let duration = std::time::Duration::from_nanos(nanos as u64);
+    format!("{duration:.1?}")
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{fit, parse_scaled_output, to_csv, Sample};
+
+    #[test]
+    fn parses_scaled_output() {
+        let series = parse_scaled_output(&[
+            "Part 1 [n=10]: 4 (10.0ns @ 10000 samples)".into(),
+            "Part 2 [n=10]: ✖ (panicked)".into(),
+            "Part 1 [n=100]: 40 (1.0µs @ 10000 samples)".into(),
+            "Part 2 [n=100]: 7 (2.0ms @ 10 samples)".into(),
+            "Part 1: 400 (10.0µs @ 1000 samples)".into(),
+        ]);
+
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].part, 1);
+        assert_eq!(
+            series[0].samples,
+            vec![
+                Sample {
+                    size: 10,
+                    nanos: 10.0
+                },
+                Sample {
+                    size: 100,
+                    nanos: 1000.0
+                }
+            ]
+        );
+        assert_eq!(series[1].samples.len(), 1);
+    }
+
+    #[test]
+    fn fits_exponent() {
+        let quadratic: Vec<Sample> = [10, 20, 40, 80]
+            .iter()
+            .map(|&size| Sample {
+                size,
+                nanos: 3.0 * (size * size) as f64,
+            })
+            .collect();
+
+        let f = fit(&quadratic).unwrap();
+        assert!((f.exponent - 2.0).abs() < 1e-9);
+        assert!((f.r_squared - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn needs_two_sizes_to_fit() {
+        let single = [Sample {
+            size: 10,
+            nanos: 1.0,
+        }];
+        assert!(fit(&single).is_none());
+    }
+
+    #[test]
+    fn formats_csv() {
+        let series = parse_scaled_output(&["Part 1 [n=10]: 4 (10.0ns @ 10000 samples)".into()]);
+        assert_eq!(to_csv(&series), "part,size_bytes,nanos\n1,10,10\n");
+    }
+}
@@ -0,0 +1,227 @@
+/// Module that renders the solution file created by `cargo scaffold`.
+/// Templates live in `templates/<name>.txt`, the built-in `blank` template is `src/template.txt`.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::template::{aoc_cli, clock, Day};
+
+const BLANK_TEMPLATE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
+
+const TEMPLATES_DIR: &str = "templates";
+
+pub const DEFAULT_TEMPLATE: &str = "blank";
+pub const DEFAULT_RETURN_TYPE: &str = "u64";
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum Error {
+    NotFound(String, Vec<String>),
+    IO(std::io::Error),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+/// Names of all available templates, sorted. Always contains the built-in `blank` template.
+pub fn available() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(TEMPLATES_DIR)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+        .collect();
+
+    if !names.iter().any(|n| n == DEFAULT_TEMPLATE) {
+        names.push(DEFAULT_TEMPLATE.into());
+    }
+
+    names.sort();
+    names
+}
+
+fn get_path_for_template(name: &str) -> PathBuf {
+    Path::new(TEMPLATES_DIR).join(format!("{name}.txt"))
+}
+
+/// Load a template by name. A `templates/blank.txt` takes precedence over the built-in one.
+pub fn load(name: &str) -> Result<String, Error> {
+    match fs::read_to_string(get_path_for_template(name)) {
+        Ok(template) => Ok(template),
+        Err(_) if name == DEFAULT_TEMPLATE => Ok(BLANK_TEMPLATE.into()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(Error::NotFound(name.into(), available()))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Values substituted into a template.
+#[derive(Clone, Debug)]
+pub struct Placeholders {
+    pub day: Day,
+    pub year: u16,
+    pub title: String,
+    pub return_type: String,
+    pub example_answers: [Option<String>; 2],
+}
+
+impl Placeholders {
+    /// Collect placeholder values for `day`. Title and example answers are read from the puzzle description,
+    /// if it has been downloaded to `data/puzzles`.
+    pub fn for_day(day: Day, return_type: &str) -> Self {
+        let puzzle = fs::read_to_string(format!("data/puzzles/{day}.md")).unwrap_or_default();
+
+        Placeholders {
+            day,
+            year: aoc_cli::get_year().unwrap_or_else(clock::current_year),
+            title: parse_title(&puzzle).unwrap_or_else(|| format!("Day {}", day.into_inner())),
+            return_type: return_type.into(),
+            example_answers: parse_example_answers(&puzzle),
+        }
+    }
+
+    /// The expected result of a part for the example, as a Rust expression of type `Option<return_type>`.
+    fn example_expr(&self, part: usize) -> String {
+        match &self.example_answers[part] {
+            Some(answer) if self.return_type == "String" => format!("Some({answer:?}.to_string())"),
+            Some(answer) if answer.parse::<i128>().is_ok() => format!("Some({answer})"),
+            _ => "None".into(),
+        }
+    }
+}
+
+/// Substitute all placeholders in `template`:
+///  - `%DAY_NUMBER%`: the day without padding, e.g. `7`.
+///  - `%YEAR%`: the configured `AOC_YEAR`, or the current year.
+///  - `%TITLE%`: the puzzle title, e.g. `Bridge Repair`.
+///  - `%RETURN_TYPE%`: the type returned by both parts, `u64` by default.
+///  - `%EXAMPLE_ONE%` / `%EXAMPLE_TWO%`: the expected example result of a part, e.g. `Some(3749)` or `None`.
+#[must_use]
+pub fn render(template: &str, placeholders: &Placeholders) -> String {
+    template
+        .replace("%DAY_NUMBER%", &placeholders.day.into_inner().to_string())
+        .replace("%YEAR%", &placeholders.year.to_string())
+        .replace("%TITLE%", &placeholders.title)
+        .replace("%RETURN_TYPE%", &placeholders.return_type)
+        .replace("%EXAMPLE_ONE%", &placeholders.example_expr(0))
+        .replace("%EXAMPLE_TWO%", &placeholders.example_expr(1))
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Parse the title from a heading like `--- Day 7: Bridge Repair ---`.
+pub fn parse_title(puzzle: &str) -> Option<String> {
+    puzzle.lines().find_map(|line| {
+        let heading = line.split("--- Day ").nth(1)?;
+        let (_, title) = heading.split_once(':')?;
+        let title = title.trim().trim_end_matches('-').trim();
+        (!title.is_empty()).then(|| title.to_string())
+    })
+}
+
+/// The example answer of each part is the last emphasized code span of its description,
+/// i.e. `<code><em>3749</em></code>` rendered as either `` *`3749`* `` or `` `*3749*` ``.
+fn parse_example_answers(puzzle: &str) -> [Option<String>; 2] {
+    let (part_one, part_two) = match puzzle.split_once("--- Part Two ---") {
+        Some((one, two)) => (one, Some(two)),
+        None => (puzzle, None),
+    };
+
+    [
+        last_emphasized_code(part_one),
+        part_two.and_then(last_emphasized_code),
+    ]
+}
+
+fn last_emphasized_code(s: &str) -> Option<String> {
+    ["*`", "`*", "<code><em>"]
+        .iter()
+        .zip(["`*", "*`", "</em></code>"])
+        .filter_map(|(open, close)| {
+            let (before, _) = s.rsplit_once(close)?;
+            let (start, answer) = before.rsplit_once(open)?;
+            Some((start.len(), answer))
+        })
+        .filter(|(_, answer)| !answer.is_empty() && !answer.contains('\n'))
+        .max_by_key(|(position, _)| *position)
+        .map(|(_, answer)| answer.to_string())
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_example_answers, parse_title, render, Placeholders, BLANK_TEMPLATE};
+    use crate::day;
+
+    const PUZZLE: &str = "\\--- Day 7: Bridge Repair ---
+----------
+
+The engineers just need the *total calibration result*, which is the sum of the test values
+from just the equations that could possibly be true. In the above example, the sum is *`3749`*.
+
+\\--- Part Two ---
+----------
+
+Now, the total calibration result is `*11387*`.
+";
+
+    fn placeholders(return_type: &str) -> Placeholders {
+        Placeholders {
+            day: day!(7),
+            year: 2024,
+            title: "Bridge Repair".into(),
+            return_type: return_type.into(),
+            example_answers: [Some("3749".into()), None],
+        }
+    }
+
+    #[test]
+    fn parses_titles() {
+        assert_eq!(parse_title(PUZZLE), Some("Bridge Repair".into()));
+        assert_eq!(
+            parse_title("## --- Day 12: Garden Groups ---"),
+            Some("Garden Groups".into())
+        );
+        assert_eq!(parse_title(""), None);
+    }
+
+    #[test]
+    fn parses_example_answers() {
+        assert_eq!(
+            parse_example_answers(PUZZLE),
+            [Some("3749".into()), Some("11387".into())]
+        );
+        assert_eq!(
+            parse_example_answers("answer is <code><em>42</em></code>."),
+            [Some("42".into()), None]
+        );
+        assert_eq!(parse_example_answers(""), [None, None]);
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        let template = "// %YEAR% day %DAY_NUMBER%: %TITLE%\nfn f() -> Option<%RETURN_TYPE%> {}\n%EXAMPLE_ONE% %EXAMPLE_TWO%";
+        assert_eq!(
+            render(template, &placeholders("u64")),
+            "// 2024 day 7: Bridge Repair\nfn f() -> Option<u64> {}\nSome(3749) None"
+        );
+        assert!(render(template, &placeholders("String")).contains("Some(\"3749\".to_string())"));
+    }
+
+    #[test]
+    fn renders_blank_template() {
+        let rendered = render(BLANK_TEMPLATE, &placeholders("u64"));
+        assert!(rendered.starts_with("advent_of_code::solution!(7);"));
+        assert!(!rendered.contains('%'));
+    }
+}
@@ -1,17 +1,60 @@
-use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use std::{collections::HashMap, fmt, fs, io::Error, str::FromStr, time::Duration};
 use tinyjson::JsonValue;
 
 use crate::template::Day;
 
 static TIMINGS_FILE_PATH: &str = "./data/timings.json";
 
+/// Version of the timings file. Files without a version store parts as formatted strings.
+const TIMINGS_FILE_VERSION: u8 = 2;
+
+/// Represents the benchmark time of a single solution part.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PartTiming {
+    pub nanos: f64,
+    /// Number of runs the time was averaged over. Unknown for timings migrated from an unversioned file.
+    pub samples: Option<u64>,
+}
+
+impl PartTiming {
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Duration::from_nanos(self.nanos.round() as u64)
+    }
+}
+
+/// Formats the same way as the solution runner, e.g. `74.0ns` or `1.5ms`.
+impl fmt::Display for PartTiming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1?}", self.duration())
+    }
+}
+
+/// Parse a duration formatted with `Debug`, e.g. `74.13ns`, into nanoseconds.
+pub fn parse_duration(s: &str) -> Option<f64> {
+    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
+        s.split(postfix).next()?.parse().ok()
+    }
+
+    // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
+    match s {
+        s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
+        s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
+        s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
+        s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
+    }
+}
+
 /// Represents benchmark times for a single day.
 #[derive(Clone, Debug)]
 pub struct Timing {
     pub day: Day,
-    pub part_1: Option<String>,
-    pub part_2: Option<String>,
+    pub part_1: Option<PartTiming>,
+    pub part_2: Option<PartTiming>,
     pub total_nanos: f64,
+    /// Highest peak heap usage of either part, in bytes.
+    pub peak_bytes: Option<u64>,
 }
 
 /// Represents benchmark times for a set of days.
@@ -30,11 +73,22 @@ impl Timings {
     }
 
     /// Rehydrate timings from a JSON file. If not present, returns empty timings.
+    /// Files written before parts were stored as numbers are migrated in place.
     pub fn read_from_file() -> Self {
-        fs::read_to_string(TIMINGS_FILE_PATH)
+        let Ok((timings, is_legacy)) = fs::read_to_string(TIMINGS_FILE_PATH)
             .map_err(|x| x.to_string())
-            .and_then(Timings::try_from)
-            .unwrap_or_default()
+            .and_then(|s| Timings::parse(&s))
+        else {
+            return Timings::default();
+        };
+
+        if is_legacy {
+            if let Err(e) = timings.store_file() {
+                eprintln!("Failed to migrate \"{TIMINGS_FILE_PATH}\": {e}");
+            }
+        }
+
+        timings
     }
 
     /// Merge two sets of timings, overwriting `self` with `other` if present.
@@ -51,7 +105,7 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|t| t.day);
         Timings { data }
     }
 
@@ -73,6 +127,10 @@ impl From<Timings> for JsonValue {
     fn from(value: Timings) -> Self {
         let mut map: HashMap<String, JsonValue> = HashMap::new();
 
+        map.insert(
+            "version".into(),
+            JsonValue::Number(f64::from(TIMINGS_FILE_VERSION)),
+        );
         map.insert(
             "data".into(),
             JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
@@ -82,26 +140,90 @@ impl From<Timings> for JsonValue {
     }
 }
 
-impl TryFrom<String> for Timings {
-    type Error = String;
+impl Timings {
+    /// Parse a timings file, returning whether it was in the unversioned format.
+    fn parse(value: &str) -> Result<(Self, bool), String> {
+        let json = JsonValue::from_str(value).or(Err("not valid JSON file."))?;
 
-    fn try_from(value: String) -> Result<Self, Self::Error> {
-        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+        let json = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?;
+
+        let is_legacy = !json.contains_key("version");
 
         let json_data = json
-            .get::<HashMap<String, JsonValue>>()
-            .ok_or("expected JSON document to be an object.")?
             .get("data")
             .ok_or("expected JSON document to have key `data`.")?
             .get::<Vec<JsonValue>>()
             .ok_or("expected `json.data` to be an array.")?;
 
-        Ok(Timings {
+        let timings = Timings {
             data: json_data
                 .iter()
                 .map(Timing::try_from)
                 .collect::<Result<_, _>>()?,
-        })
+        };
+
+        Ok((timings, is_legacy))
+    }
+}
+
+impl TryFrom<String> for Timings {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Timings::parse(&value).map(|(timings, _)| timings)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&PartTiming> for JsonValue {
+    fn from(value: &PartTiming) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("nanos".into(), JsonValue::Number(value.nanos));
+        #[allow(clippy::cast_precision_loss)]
+        map.insert(
+            "samples".into(),
+            value
+                .samples
+                .map_or(JsonValue::Null, |x| JsonValue::Number(x as f64)),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for PartTiming {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        // unversioned files store the formatted duration.
+        if let Some(s) = value.get::<String>() {
+            let nanos = parse_duration(s).ok_or("Expected part to be a formatted duration.")?;
+            return Ok(PartTiming {
+                nanos,
+                samples: None,
+            });
+        }
+
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected part to be a JSON object.")?;
+
+        let nanos = json
+            .get("nanos")
+            .and_then(|v| v.get::<f64>().copied())
+            .ok_or("Expected part.nanos to be a number.")?;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let samples = json
+            .get("samples")
+            .and_then(|v| v.get::<f64>())
+            .map(|&x| x as u64);
+
+        Ok(PartTiming { nanos, samples })
     }
 }
 
@@ -113,9 +235,15 @@ impl From<&Timing> for JsonValue {
 
         map.insert("day".into(), JsonValue::String(value.day.to_string()));
         map.insert("total_nanos".into(), JsonValue::Number(value.total_nanos));
+        map.insert(
+            "peak_bytes".into(),
+            value
+                .peak_bytes
+                .map_or(JsonValue::Null, |x| JsonValue::Number(x as f64)),
+        );
 
-        let part_1 = value.part_1.clone().map(JsonValue::String);
-        let part_2 = value.part_2.clone().map(JsonValue::String);
+        let part_1 = value.part_1.as_ref().map(JsonValue::from);
+        let part_2 = value.part_2.as_ref().map(JsonValue::from);
 
         map.insert(
             "part_1".into(),
@@ -153,24 +281,32 @@ impl TryFrom<&JsonValue> for Timing {
 
         let part_1 = json
             .get("part_1")
-            .map(|v| if v.is_null() { None } else { v.get::<String>() })
-            .ok_or("Expected timing.part_1 to be null or string.")?;
+            .map(|v| (!v.is_null()).then(|| PartTiming::try_from(v)).transpose())
+            .ok_or("Expected timing.part_1 to be null or a part timing.")??;
 
         let part_2 = json
             .get("part_2")
-            .map(|v| if v.is_null() { None } else { v.get::<String>() })
-            .ok_or("Expected timing.part_2 to be null or string.")?;
+            .map(|v| (!v.is_null()).then(|| PartTiming::try_from(v)).transpose())
+            .ok_or("Expected timing.part_2 to be null or a part timing.")??;
 
         let total_nanos = json
             .get("total_nanos")
             .and_then(|v| v.get::<f64>().copied())
             .ok_or("Expected timing.total_nanos to be a number.")?;
 
+        // not present in files written before memory was measured.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let peak_bytes = json
+            .get("peak_bytes")
+            .and_then(|v| v.get::<f64>())
+            .map(|&x| x as u64);
+
         Ok(Timing {
             day,
-            part_1: part_1.cloned(),
-            part_2: part_2.cloned(),
+            part_1,
+            part_2,
             total_nanos,
+            peak_bytes,
         })
     }
 }
@@ -181,46 +317,102 @@ impl TryFrom<&JsonValue> for Timing {
 mod tests {
     use crate::day;
 
-    use super::{Timing, Timings};
+    use super::{PartTiming, Timing, Timings};
 
     fn get_mock_timings() -> Timings {
         Timings {
             data: vec![
                 Timing {
                     day: day!(1),
-                    part_1: Some("10ms".into()),
-                    part_2: Some("20ms".into()),
+                    part_1: Some(PartTiming {
+                        nanos: 10e+6,
+                        samples: Some(10),
+                    }),
+                    part_2: Some(PartTiming {
+                        nanos: 20e+6,
+                        samples: Some(10),
+                    }),
                     total_nanos: 3e+10,
+                    peak_bytes: None,
                 },
                 Timing {
                     day: day!(2),
-                    part_1: Some("30ms".into()),
-                    part_2: Some("40ms".into()),
+                    part_1: Some(PartTiming {
+                        nanos: 30e+6,
+                        samples: Some(10),
+                    }),
+                    part_2: Some(PartTiming {
+                        nanos: 40e+6,
+                        samples: Some(10),
+                    }),
                     total_nanos: 7e+10,
+                    peak_bytes: None,
                 },
                 Timing {
                     day: day!(4),
-                    part_1: Some("40ms".into()),
+                    part_1: Some(PartTiming {
+                        nanos: 40e+6,
+                        samples: Some(10),
+                    }),
                     part_2: None,
                     total_nanos: 4e+10,
+                    peak_bytes: None,
                 },
             ],
         }
     }
 
     mod deserialization {
-        use crate::{day, template::timings::Timings};
+        use super::get_mock_timings;
+        use crate::{
+            day,
+            template::timings::{PartTiming, Timings},
+        };
 
         #[test]
         fn handles_json_timings() {
-            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": null, "total_nanos": 1000000000 }] }"#.to_string();
+            let json = r#"{ "version": 2, "data": [{ "day": "01", "part_1": { "nanos": 1000000, "samples": 10 }, "part_2": null, "total_nanos": 1000000 }] }"#.to_string();
             let timings = Timings::try_from(json).unwrap();
             assert_eq!(timings.data.len(), 1);
             let timing = timings.data.first().unwrap();
             assert_eq!(timing.day, day!(1));
-            assert_eq!(timing.part_1, Some("1ms".to_string()));
+            assert_eq!(
+                timing.part_1,
+                Some(PartTiming {
+                    nanos: 1_000_000_f64,
+                    samples: Some(10)
+                })
+            );
             assert_eq!(timing.part_2, None);
-            assert_eq!(timing.total_nanos, 1_000_000_000_f64);
+            assert_eq!(timing.total_nanos, 1_000_000_f64);
+        }
+
+        #[test]
+        fn migrates_unversioned_timings() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "74.0ns", "part_2": "1.5ms", "total_nanos": 1500074.0 }] }"#.to_string();
+            let (timings, is_legacy) = Timings::parse(&json).unwrap();
+            assert!(is_legacy);
+            let timing = timings.data.first().unwrap();
+            assert_eq!(
+                timing.part_2,
+                Some(PartTiming {
+                    nanos: 1_500_000_f64,
+                    samples: None
+                })
+            );
+            // formatting a migrated part yields the stored string.
+            assert_eq!(timing.part_1.unwrap().to_string(), "74.0ns");
+            assert_eq!(timing.part_2.unwrap().to_string(), "1.5ms");
+        }
+
+        #[test]
+        fn round_trips_json_timings() {
+            let json = tinyjson::JsonValue::from(get_mock_timings())
+                .stringify()
+                .unwrap();
+            let (timings, is_legacy) = Timings::parse(&json).unwrap();
+            assert!(!is_legacy);
+            assert_eq!(timings.data[0].part_1, get_mock_timings().data[0].part_1);
         }
 
         #[test]
@@ -271,7 +463,7 @@ mod tests {
     mod is_day_complete {
         use crate::{
             day,
-            template::timings::{Timing, Timings},
+            template::timings::{PartTiming, Timing, Timings},
         };
 
         #[test]
@@ -279,13 +471,20 @@ mod tests {
             let timings = Timings {
                 data: vec![Timing {
                     day: day!(1),
-                    part_1: Some("1ms".into()),
-                    part_2: Some("2ms".into()),
+                    part_1: Some(PartTiming {
+                        nanos: 1e+6,
+                        samples: Some(10),
+                    }),
+                    part_2: Some(PartTiming {
+                        nanos: 2e+6,
+                        samples: Some(10),
+                    }),
                     total_nanos: 3_000_000_000_f64,
+                    peak_bytes: None,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(day!(1)), true);
         }
 
         #[test]
@@ -293,13 +492,17 @@ mod tests {
             let timings = Timings {
                 data: vec![Timing {
                     day: day!(1),
-                    part_1: Some("1ms".into()),
+                    part_1: Some(PartTiming {
+                        nanos: 1e+6,
+                        samples: Some(10),
+                    }),
                     part_2: None,
                     total_nanos: 1_000_000_000_f64,
+                    peak_bytes: None,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
 
         #[test]
@@ -310,17 +513,18 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0.0,
+                    peak_bytes: None,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
     }
 
     mod merge {
         use crate::{
             day,
-            template::timings::{Timing, Timings},
+            template::timings::{PartTiming, Timing, Timings},
         };
 
         use super::get_mock_timings;
@@ -334,6 +538,7 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    peak_bytes: None,
                 }],
             };
             let merged = timings.merge(&other);
@@ -354,6 +559,7 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    peak_bytes: None,
                 }],
             };
             let merged = timings.merge(&other);
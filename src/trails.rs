@@ -0,0 +1,399 @@
+/// Hiking trails on a topographic map, like 2024 day 10. One pass over the cells in order of height finds,
+/// for every cell, how many summits its trails reach and how many distinct trails there are, and the
+/// counts let trails be enumerated or picked by index without searching dead ends.
+use std::ops::RangeInclusive;
+
+use crate::grid::{Grid, Point};
+use crate::parse::{self, Span};
+use crate::visualize::{Colour, Frame};
+
+pub type Height = u8;
+
+/// Where trails start and end and how much they may climb in one step. Steps must all climb or all
+/// descend, so trails never loop and there are finitely many of them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    pub start: Height,
+    pub end: Height,
+    pub climb: RangeInclusive<i32>,
+}
+
+impl Rule {
+    /// From height 0 to 9, climbing by exactly one each step.
+    pub const PUZZLE: Rule = Rule {
+        start: 0,
+        end: 9,
+        climb: 1..=1,
+    };
+
+    /// The same trails walked from the other end.
+    #[must_use]
+    pub fn reversed(&self) -> Rule {
+        Rule {
+            start: self.end,
+            end: self.start,
+            climb: -self.climb.end()..=-self.climb.start(),
+        }
+    }
+
+    fn allows(&self, from: Height, to: Height) -> bool {
+        self.climb.contains(&(i32::from(to) - i32::from(from)))
+    }
+
+    /// `1` if trails climb and `-1` if they descend.
+    fn direction(&self) -> i32 {
+        assert!(
+            !self.climb.contains(&0) && self.climb.start().signum() == self.climb.end().signum(),
+            "trails must either always climb or always descend"
+        );
+        self.climb.start().signum()
+    }
+}
+
+/// Heights of the map, `None` for impassable cells drawn as `.`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TopoMap {
+    pub heights: Grid<Option<Height>>,
+}
+
+impl TopoMap {
+    pub fn parse(input: &str) -> Result<TopoMap, parse::Error> {
+        let heights = Span::new(input).grid(|c| match c {
+            '.' => Some(None),
+            _ => c.to_digit(10).map(|d| Some(d as Height)),
+        })?;
+        Ok(TopoMap { heights })
+    }
+
+    /// The neighbours of `p` a trail may step to, clockwise starting above it.
+    fn steps<'a>(&'a self, rule: &'a Rule, p: Point) -> impl Iterator<Item = Point> + 'a {
+        let from = self.heights[p];
+        self.heights
+            .neighbours(p)
+            .filter_map(move |(next, &to)| match (from, to) {
+                (Some(from), Some(to)) if rule.allows(from, to) => Some(next),
+                _ => None,
+            })
+    }
+
+    /// Count the summits and trails of every cell. Cells are visited furthest along a trail first, so the
+    /// cells a step leads to are always done before the cell itself.
+    ///
+    /// # Panics
+    ///
+    /// If the steps of `rule` do not all climb or all descend.
+    #[must_use]
+    pub fn analyse(&self, rule: Rule) -> Analysis<'_> {
+        let direction = rule.direction();
+        let mut order: Vec<Point> = self
+            .heights
+            .iter_coordinates()
+            .filter(|&p| self.heights[p].is_some())
+            .collect();
+        order.sort_by_key(|&p| -direction * i32::from(self.heights[p].unwrap()));
+
+        let dimension = self.heights.dimension();
+        let mut index = Grid::new_with_dimensions_uniform(dimension, None);
+        let mut summits: usize = 0;
+        for &p in &order {
+            if self.heights[p] == Some(rule.end) {
+                index[p] = Some(summits);
+                summits += 1;
+            }
+        }
+
+        // one bit per summit, for every cell.
+        let words = summits.div_ceil(64);
+        let mut reached: Grid<Vec<u64>> = Grid::new_with_dimensions_uniform(dimension, vec![]);
+        let mut ratings = Grid::new_with_dimensions_uniform(dimension, 0);
+        for &p in &order {
+            if let Some(i) = index[p] {
+                reached[p] = vec![0; words];
+                reached[p][i / 64] |= 1 << (i % 64);
+                ratings[p] = 1;
+                continue;
+            }
+            let mut bits = vec![];
+            for next in self.steps(&rule, p) {
+                if ratings[next] == 0 {
+                    continue;
+                }
+                ratings[p] += ratings[next];
+                bits.resize(words, 0);
+                for (bit, word) in bits.iter_mut().zip(&reached[next]) {
+                    *bit |= word;
+                }
+            }
+            reached[p] = bits;
+        }
+
+        Analysis {
+            map: self,
+            scores: reached.map(|bits| bits.iter().map(|w| w.count_ones() as usize).sum()),
+            ratings,
+            rule,
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The trails of a map under one rule. Trails end at the first summit they reach.
+#[derive(Clone, Debug)]
+pub struct Analysis<'a> {
+    map: &'a TopoMap,
+    pub rule: Rule,
+    /// The number of summits reachable from each cell.
+    pub scores: Grid<usize>,
+    /// The number of distinct trails from each cell to a summit.
+    pub ratings: Grid<u64>,
+}
+
+impl Analysis<'_> {
+    pub fn trailheads(&self) -> impl Iterator<Item = Point> + '_ {
+        self.map
+            .heights
+            .iter_coordinates()
+            .filter(|&p| self.map.heights[p] == Some(self.rule.start))
+    }
+
+    /// The summed scores of all trailheads.
+    #[must_use]
+    pub fn score(&self) -> usize {
+        self.trailheads().map(|p| self.scores[p]).sum()
+    }
+
+    /// The summed ratings of all trailheads.
+    #[must_use]
+    pub fn rating(&self) -> u64 {
+        self.trailheads().map(|p| self.ratings[p]).sum()
+    }
+
+    /// Every trail from `from` to a summit, in the order of [`Analysis::trail`].
+    #[must_use]
+    pub fn trails(&self, from: Point) -> Trails<'_> {
+        let stack = match self.ratings.get(from) {
+            Some(&rating) if rating > 0 => vec![vec![from]],
+            _ => vec![],
+        };
+        Trails {
+            analysis: self,
+            stack,
+        }
+    }
+
+    /// The trail number `index` from `from`, below its rating, e.g. to sample trails uniformly.
+    #[must_use]
+    pub fn trail(&self, from: Point, mut index: u64) -> Option<Vec<Point>> {
+        if index >= *self.ratings.get(from)? {
+            return None;
+        }
+        let mut trail = vec![from];
+        let mut p = from;
+        while self.map.heights[p] != Some(self.rule.end) {
+            for next in self.map.steps(&self.rule, p) {
+                if index < self.ratings[next] {
+                    p = next;
+                    break;
+                }
+                index -= self.ratings[next];
+            }
+            trail.push(p);
+        }
+        Some(trail)
+    }
+
+    /// Ratings on a logarithmic scale from `1` to `9`, cells without trails as `.` and trailheads in
+    /// yellow.
+    #[must_use]
+    pub fn heat_map(&self) -> Frame {
+        let max = self.ratings.0.iter().flatten().copied().max().unwrap_or(0);
+        let levels = max.max(2).ilog2();
+        Frame::new(&self.ratings, |&rating| match rating {
+            0 => '.',
+            _ => char::from_digit(1 + 8 * rating.ilog2() / levels, 10).unwrap(),
+        })
+        .paint('.', Colour::Grey)
+        .paint('1', Colour::Blue)
+        .paint('2', Colour::Blue)
+        .paint('3', Colour::Cyan)
+        .paint('4', Colour::Cyan)
+        .paint('5', Colour::Green)
+        .paint('6', Colour::Green)
+        .paint('7', Colour::Magenta)
+        .paint('8', Colour::Magenta)
+        .paint('9', Colour::Red)
+        .colour_all(
+            self.trailheads().filter(|&p| self.ratings[p] > 0),
+            Colour::Yellow,
+        )
+        .caption(format!("trails from each cell, up to {max}"))
+    }
+}
+
+/// Depth-first enumeration of trails, only entering cells that lead to a summit.
+pub struct Trails<'a> {
+    analysis: &'a Analysis<'a>,
+    stack: Vec<Vec<Point>>,
+}
+
+impl Iterator for Trails<'_> {
+    type Item = Vec<Point>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Analysis { map, rule, .. } = self.analysis;
+        while let Some(trail) = self.stack.pop() {
+            let last = *trail.last().unwrap();
+            if map.heights[last] == Some(rule.end) {
+                return Some(trail);
+            }
+            let steps: Vec<Point> = map
+                .steps(rule, last)
+                .filter(|&next| self.analysis.ratings[next] > 0)
+                .collect();
+            for &next in steps.iter().rev() {
+                let mut longer = trail.clone();
+                longer.push(next);
+                self.stack.push(longer);
+            }
+        }
+        None
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::collections::HashSet;
+
+    use lina::point2;
+    use proptest::prelude::*;
+
+    use super::{Rule, TopoMap};
+    use crate::grid::{Grid, Point};
+
+    const EXAMPLE: &str = "\
+89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732
+";
+
+    /// Every trail from `p`, found without the counts.
+    fn brute_force(
+        map: &TopoMap,
+        rule: &Rule,
+        trail: &mut Vec<Point>,
+        found: &mut Vec<Vec<Point>>,
+    ) {
+        let p = *trail.last().unwrap();
+        if map.heights[p] == Some(rule.end) {
+            found.push(trail.clone());
+            return;
+        }
+        for next in map.steps(rule, p).collect::<Vec<_>>() {
+            trail.push(next);
+            brute_force(map, rule, trail, found);
+            trail.pop();
+        }
+    }
+
+    fn small_map() -> impl Strategy<Value = TopoMap> {
+        (1..6usize, 1..6usize)
+            .prop_flat_map(|(w, h)| {
+                let cells = prop::collection::vec(prop::option::weighted(0.9, 0..5u8), w * h);
+                (Just(w), cells)
+            })
+            .prop_map(|(w, cells)| {
+                let rows = cells.chunks(w).map(<[_]>::to_vec).collect();
+                TopoMap {
+                    heights: Grid::new(rows),
+                }
+            })
+    }
+
+    #[test]
+    fn scores_and_rates_the_example() {
+        let map = TopoMap::parse(EXAMPLE).unwrap();
+        let analysis = map.analyse(Rule::PUZZLE);
+        assert_eq!(analysis.score(), 36);
+        assert_eq!(analysis.rating(), 81);
+        assert_eq!(analysis.trailheads().count(), 9);
+        assert_eq!(analysis.scores[point2(2, 0)], 5);
+        assert_eq!(analysis.ratings[point2(2, 0)], 20);
+
+        let reversed = map.analyse(Rule::PUZZLE.reversed());
+        assert_eq!(reversed.score(), 36);
+        assert_eq!(reversed.rating(), 81);
+    }
+
+    #[test]
+    fn parses_impassable_cells() {
+        let map = TopoMap::parse("01234\n....5\n.9876\n").unwrap();
+        assert_eq!(map.heights[point2(0, 1)], None);
+        let analysis = map.analyse(Rule::PUZZLE);
+        assert_eq!(analysis.rating(), 1);
+        let trail = analysis.trail(point2(0, 0), 0).unwrap();
+        assert_eq!(trail.len(), 10);
+        assert_eq!(trail[5], point2(4, 1));
+        assert_eq!(TopoMap::parse("01\n2x\n").unwrap_err().column, 2);
+    }
+
+    #[test]
+    fn follows_other_rules() {
+        let map = TopoMap::parse("0246\n1357\n").unwrap();
+        let rule = Rule {
+            start: 0,
+            end: 7,
+            climb: 1..=2,
+        };
+        let analysis = map.analyse(rule);
+        let trails: Vec<_> = analysis.trails(point2(0, 0)).collect();
+        assert_eq!(trails.len() as u64, analysis.ratings[point2(0, 0)]);
+        assert!(trails.contains(&vec![
+            point2(0, 0),
+            point2(1, 0),
+            point2(2, 0),
+            point2(3, 0),
+            point2(3, 1)
+        ]));
+        assert_eq!(analysis.heat_map().rows.len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "always climb")]
+    fn rejects_looping_rules() {
+        let map = TopoMap::parse("01\n").unwrap();
+        let _ = map.analyse(Rule {
+            start: 0,
+            end: 1,
+            climb: -1..=1,
+        });
+    }
+
+    proptest! {
+        #[test]
+        fn matches_brute_force(map in small_map(), climb in prop::sample::select(vec![1..=1, 1..=2, -2..=-1]), start in 0..5u8, end in 0..5u8) {
+            let rule = Rule { start, end, climb };
+            let analysis = map.analyse(rule.clone());
+            for p in map.heights.iter_coordinates().filter(|&p| map.heights[p].is_some()) {
+                let mut expected = vec![];
+                brute_force(&map, &rule, &mut vec![p], &mut expected);
+                let summits: HashSet<Point> = expected.iter().map(|t| *t.last().unwrap()).collect();
+                prop_assert_eq!(analysis.ratings[p], expected.len() as u64);
+                prop_assert_eq!(analysis.scores[p], summits.len());
+                prop_assert_eq!(&analysis.trails(p).collect::<Vec<_>>(), &expected);
+                for (i, trail) in expected.iter().enumerate() {
+                    let found = analysis.trail(p, i as u64);
+                    prop_assert_eq!(found.as_ref(), Some(trail));
+                }
+                prop_assert_eq!(analysis.trail(p, expected.len() as u64), None);
+            }
+        }
+    }
+}
@@ -0,0 +1,432 @@
+/// Module that exports recorded frames as an animated GIF or a sequence of PNG images to `data/visuals/DD/`.
+/// Every cell is drawn as a square block of pixels in the colour it has in the terminal.
+use std::{fmt::Display, io, str::FromStr};
+
+use lina::point2;
+
+use super::{Colour, Frame};
+use crate::template::Day;
+
+/// Delay between two frames of a GIF in hundredths of a second.
+const GIF_DELAY: u16 = 5;
+const DEFAULT_CELL_SIZE: u16 = 4;
+
+/// Characters drawn as background unless the cell is coloured or highlighted.
+const EMPTY: [char; 2] = [' ', '.'];
+
+#[derive(Debug)]
+pub enum Error {
+    FeatureDisabled,
+    NoFrames,
+    TooLarge,
+    Encoder(String),
+    IO(io::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::FeatureDisabled => write!(f, "the `visualize` feature is disabled"),
+            Error::NoFrames => write!(f, "no frames to export"),
+            Error::TooLarge => write!(f, "the image is wider or higher than 65535 pixels"),
+            Error::Encoder(message) => write!(f, "failed to encode the image: {message}"),
+            Error::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Gif,
+    Png,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gif" => Ok(Format::Gif),
+            "png" => Ok(Format::Png),
+            _ => Err(format!(
+                "unknown image format `{s}`, expected `gif` or `png`"
+            )),
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Format::Gif => "gif",
+            Format::Png => "png",
+        })
+    }
+}
+
+pub type Rgb = [u8; 3];
+
+/// Colours of exported images. `colours` is indexed in the order of the [`Colour`] variants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Palette {
+    pub background: Rgb,
+    pub foreground: Rgb,
+    pub highlight: Rgb,
+    pub colours: [Rgb; 7],
+}
+
+impl Palette {
+    pub const DARK: Palette = Palette {
+        background: [0x0f, 0x0f, 0x23],
+        foreground: [0xcc, 0xcc, 0xcc],
+        highlight: [0xff, 0xff, 0x66],
+        colours: [
+            [0xe0, 0x4b, 0x4b],
+            [0x00, 0x99, 0x00],
+            [0xff, 0xcc, 0x33],
+            [0x4b, 0x7b, 0xe0],
+            [0xc0, 0x5b, 0xd0],
+            [0x33, 0xcc, 0xcc],
+            [0x44, 0x44, 0x55],
+        ],
+    };
+
+    pub const LIGHT: Palette = Palette {
+        background: [0xff, 0xff, 0xff],
+        foreground: [0x33, 0x33, 0x33],
+        highlight: [0xff, 0x66, 0x00],
+        colours: [
+            [0xcc, 0x22, 0x22],
+            [0x22, 0x88, 0x22],
+            [0xcc, 0x99, 0x00],
+            [0x22, 0x55, 0xcc],
+            [0x99, 0x33, 0xaa],
+            [0x11, 0x99, 0x99],
+            [0xbb, 0xbb, 0xbb],
+        ],
+    };
+
+    /// All colours in the order of the indices returned by [`cell_index`].
+    fn entries(&self) -> Vec<Rgb> {
+        [self.background, self.foreground, self.highlight]
+            .into_iter()
+            .chain(self.colours)
+            .collect()
+    }
+}
+
+/// `dark`, `light` or ten comma separated `#rrggbb` colours: background, foreground, highlight and
+/// then red, green, yellow, blue, magenta, cyan and grey.
+impl FromStr for Palette {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dark" => return Ok(Palette::DARK),
+            "light" => return Ok(Palette::LIGHT),
+            _ => {}
+        }
+
+        let entries = s
+            .split(',')
+            .map(|hex| parse_hex(hex.trim()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let &[background, foreground, highlight, ref colours @ ..] = &entries[..] else {
+            return Err(format!(
+                "expected `dark`, `light` or 10 colours, found {} colours",
+                entries.len()
+            ));
+        };
+        let colours = <[Rgb; 7]>::try_from(colours)
+            .map_err(|_| format!("expected 10 colours, found {}", entries.len()))?;
+
+        Ok(Palette {
+            background,
+            foreground,
+            highlight,
+            colours,
+        })
+    }
+}
+
+impl Display for Palette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hex: Vec<String> = self
+            .entries()
+            .iter()
+            .map(|[r, g, b]| format!("#{r:02x}{g:02x}{b:02x}"))
+            .collect();
+        f.write_str(&hex.join(","))
+    }
+}
+
+fn parse_hex(hex: &str) -> Result<Rgb, String> {
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+    let value = (digits.len() == 6)
+        .then(|| u32::from_str_radix(digits, 16).ok())
+        .flatten()
+        .ok_or_else(|| format!("invalid colour `{hex}`, expected `#rrggbb`"))?;
+    let [_, r, g, b] = value.to_be_bytes();
+    Ok([r, g, b])
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// How `cargo solve <day> --visualize --export <format>` writes frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    pub format: Format,
+    pub cell_size: u16,
+    pub palette: Palette,
+}
+
+impl Options {
+    /// Read `--export <format>`, `--cell-size <pixels>` and `--palette <palette>`.
+    /// Returns `None` without `--export`.
+    pub fn parse(args: &mut pico_args::Arguments) -> Result<Option<Options>, pico_args::Error> {
+        let cell_size = args.opt_value_from_fn("--cell-size", parse_cell_size)?;
+        let palette = args.opt_value_from_str("--palette")?;
+        let Some(format) = args.opt_value_from_str("--export")? else {
+            return Ok(None);
+        };
+
+        Ok(Some(Options {
+            format,
+            cell_size: cell_size.unwrap_or(DEFAULT_CELL_SIZE),
+            palette: palette.unwrap_or(Palette::DARK),
+        }))
+    }
+
+    /// The arguments [`Options::parse`] reads these options from.
+    #[must_use]
+    pub fn to_args(&self) -> Vec<String> {
+        vec![
+            "--export".to_string(),
+            self.format.to_string(),
+            "--cell-size".to_string(),
+            self.cell_size.to_string(),
+            "--palette".to_string(),
+            self.palette.to_string(),
+        ]
+    }
+}
+
+/// A cell size in pixels, at least 1 so images are not empty.
+fn parse_cell_size(s: &str) -> Result<u16, String> {
+    match s.parse() {
+        Ok(0) => Err("the cell size must be at least 1 pixel".into()),
+        Ok(size) => Ok(size),
+        Err(e) => Err(format!("invalid cell size `{s}`: {e}")),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Index into [`Palette::entries`] of the cell at `(x, y)`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+fn cell_index(frame: &Frame, x: usize, y: usize, c: Option<char>) -> u8 {
+    let p = point2(x as i32, y as i32);
+    if frame.is_highlighted(p) {
+        return 2;
+    }
+
+    let colour = frame.point_colour(p).or_else(|| {
+        c.filter(|c| !EMPTY.contains(c))
+            .and_then(|c| frame.char_colour(c))
+    });
+    match (colour, c) {
+        (Some(colour), _) => 3 + colour as u8,
+        (None, Some(c)) if !EMPTY.contains(&c) => 1,
+        _ => 0,
+    }
+}
+
+/// Width and height in cells that fit every frame.
+fn dimension(frames: &[Frame]) -> (usize, usize) {
+    let width = frames
+        .iter()
+        .flat_map(|frame| frame.rows.iter().map(|row| row.chars().count()))
+        .max()
+        .unwrap_or(0);
+    let height = frames
+        .iter()
+        .map(|frame| frame.rows.len())
+        .max()
+        .unwrap_or(0);
+    (width, height)
+}
+
+/// Palette indices of every pixel of `frame`, row by row.
+fn pixels(frame: &Frame, (width, height): (usize, usize), cell_size: usize) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(width * height * cell_size * cell_size);
+    for y in 0..height {
+        let chars: Vec<char> = frame
+            .rows
+            .get(y)
+            .map(|row| row.chars().collect())
+            .unwrap_or_default();
+        let row: Vec<u8> = (0..width)
+            .flat_map(|x| {
+                let index = cell_index(frame, x, y, chars.get(x).copied());
+                std::iter::repeat_n(index, cell_size)
+            })
+            .collect();
+        for _ in 0..cell_size {
+            pixels.extend_from_slice(&row);
+        }
+    }
+    pixels
+}
+
+#[must_use]
+pub fn get_path_for_visual(day: Day, part: u8, frame: Option<usize>, format: Format) -> String {
+    match frame {
+        Some(frame) => format!("data/visuals/{day}/part{part}-{frame:05}.{format}"),
+        None => format!("data/visuals/{day}/part{part}.{format}"),
+    }
+}
+
+/// Write `frames` of a part to `data/visuals/<day>/`, returns the path of the GIF or of the first PNG.
+#[cfg(feature = "visualize")]
+pub fn export(frames: &[Frame], day: Day, part: u8, options: &Options) -> Result<String, Error> {
+    use std::{fs, io::BufWriter};
+
+    if frames.is_empty() {
+        return Err(Error::NoFrames);
+    }
+
+    let cell_size = usize::from(options.cell_size);
+    let (columns, rows) = dimension(frames);
+    let (Ok(width), Ok(height)) = (
+        u16::try_from(columns * cell_size),
+        u16::try_from(rows * cell_size),
+    ) else {
+        return Err(Error::TooLarge);
+    };
+
+    fs::create_dir_all(format!("data/visuals/{day}"))?;
+    let palette: Vec<u8> = options.palette.entries().concat();
+
+    match options.format {
+        Format::Gif => {
+            let path = get_path_for_visual(day, part, None, Format::Gif);
+            let file = BufWriter::new(fs::File::create(&path)?);
+            let encoder_error = |e: gif::EncodingError| Error::Encoder(e.to_string());
+
+            let mut encoder =
+                gif::Encoder::new(file, width, height, &palette).map_err(encoder_error)?;
+            encoder
+                .set_repeat(gif::Repeat::Infinite)
+                .map_err(encoder_error)?;
+
+            for frame in frames {
+                let pixels = pixels(frame, (columns, rows), cell_size);
+                let mut image = gif::Frame::from_indexed_pixels(width, height, pixels, None);
+                image.delay = GIF_DELAY;
+                encoder.write_frame(&image).map_err(encoder_error)?;
+            }
+            Ok(path)
+        }
+        Format::Png => {
+            let encoder_error = |e: png::EncodingError| Error::Encoder(e.to_string());
+
+            for (i, frame) in frames.iter().enumerate() {
+                let path = get_path_for_visual(day, part, Some(i + 1), Format::Png);
+                let file = BufWriter::new(fs::File::create(&path)?);
+
+                let mut encoder = png::Encoder::new(file, width.into(), height.into());
+                encoder.set_color(png::ColorType::Indexed);
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder.set_palette(palette.clone());
+
+                let mut writer = encoder.write_header().map_err(encoder_error)?;
+                writer
+                    .write_image_data(&pixels(frame, (columns, rows), cell_size))
+                    .map_err(encoder_error)?;
+            }
+            Ok(get_path_for_visual(day, part, Some(1), Format::Png))
+        }
+    }
+}
+
+#[cfg(not(feature = "visualize"))]
+pub fn export(
+    _frames: &[Frame],
+    _day: Day,
+    _part: u8,
+    _options: &Options,
+) -> Result<String, Error> {
+    Err(Error::FeatureDisabled)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use lina::point2;
+
+    use super::{cell_index, dimension, pixels, Format, Options, Palette};
+    use crate::grid::Grid;
+    use crate::visualize::{Colour, Frame};
+
+    #[test]
+    fn parses_palettes() {
+        assert_eq!("dark".parse(), Ok(Palette::DARK));
+        assert_eq!(Palette::LIGHT.to_string().parse(), Ok(Palette::LIGHT));
+        assert!("#000000,#ffffff".parse::<Palette>().is_err());
+        assert!("light,#ffffff".parse::<Palette>().is_err());
+    }
+
+    #[test]
+    fn round_trips_options() {
+        let options = Options {
+            format: Format::Png,
+            cell_size: 2,
+            palette: Palette::LIGHT,
+        };
+        let args = options.to_args().into_iter().map(Into::into).collect();
+        let mut args = pico_args::Arguments::from_vec(args);
+        assert_eq!(Options::parse(&mut args).unwrap(), Some(options));
+
+        let mut args = pico_args::Arguments::from_vec(vec!["--cell-size".into(), "2".into()]);
+        assert_eq!(Options::parse(&mut args).unwrap(), None);
+
+        let args = ["--export", "gif", "--cell-size", "0"]
+            .map(Into::into)
+            .to_vec();
+        let error = Options::parse(&mut pico_args::Arguments::from_vec(args)).unwrap_err();
+        assert!(error.to_string().contains("at least 1 pixel"));
+    }
+
+    #[test]
+    fn draws_cells() {
+        let grid = Grid::read("#.\n.O", |c| c);
+        let frame = Frame::chars(&grid)
+            .paint('O', Colour::Red)
+            .paint('.', Colour::Grey)
+            .colour(point2(0, 1), Colour::Green)
+            .highlight(point2(1, 0));
+
+        // '#' foreground, highlight, explicitly coloured '.', red 'O'.
+        assert_eq!(cell_index(&frame, 0, 0, Some('#')), 1);
+        assert_eq!(cell_index(&frame, 1, 0, Some('.')), 2);
+        assert_eq!(cell_index(&frame, 0, 1, Some('.')), 3 + Colour::Green as u8);
+        assert_eq!(cell_index(&frame, 1, 1, Some('O')), 3);
+
+        let small = Frame::chars(&Grid::read("#", |c| c));
+        let frames = [frame, small];
+        assert_eq!(dimension(&frames), (2, 2));
+        assert_eq!(
+            pixels(&frames[1], (2, 2), 2),
+            vec![1, 1, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+    }
+}
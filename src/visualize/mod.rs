@@ -0,0 +1,410 @@
+/// Frames of a simulation that `cargo solve <day> --visualize` plays back in the terminal.
+/// Solutions emit frames with [`frame`], which only calls its closure when the `visualize` feature is
+/// enabled, so the hooks compile away in normal and benchmark runs.
+use std::collections::{HashMap, HashSet};
+
+use lina::point2;
+
+use crate::grid::{Grid, Point};
+use crate::template::ANSI_RESET;
+
+pub mod image;
+
+/// Whether frames are recorded, for solutions that need extra bookkeeping to draw them.
+pub const ENABLED: bool = cfg!(feature = "visualize");
+
+/// Frames beyond this are dropped, so long simulations do not exhaust memory.
+pub const MAX_FRAMES: usize = 10_000;
+
+const ANSI_REVERSE: &str = "\x1b[7m";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Colour {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Grey,
+}
+
+impl Colour {
+    fn code(self) -> &'static str {
+        match self {
+            Colour::Red => "\x1b[31m",
+            Colour::Green => "\x1b[32m",
+            Colour::Yellow => "\x1b[33m",
+            Colour::Blue => "\x1b[34m",
+            Colour::Magenta => "\x1b[35m",
+            Colour::Cyan => "\x1b[36m",
+            Colour::Grey => "\x1b[90m",
+        }
+    }
+}
+
+/// A snapshot of a grid with one character per cell.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Frame {
+    pub rows: Vec<String>,
+    pub caption: String,
+    palette: Vec<(char, Colour)>,
+    /// By position, as they are looked up for every cell drawn.
+    colours: HashMap<Point, Colour>,
+    highlights: HashSet<Point>,
+}
+
+impl Frame {
+    /// Draw every cell of `grid` as the character returned by `f`.
+    #[must_use]
+    pub fn new<T>(grid: &Grid<T>, f: impl Fn(&T) -> char) -> Self {
+        Frame {
+            rows: grid
+                .0
+                .iter()
+                .map(|row| row.iter().map(&f).collect())
+                .collect(),
+            ..Frame::default()
+        }
+    }
+
+    /// Draw every cell of `grid` as the character it converts to.
+    #[must_use]
+    pub fn chars<T: Copy + Into<char>>(grid: &Grid<T>) -> Self {
+        Frame::new(grid, |&x| x.into())
+    }
+
+    #[must_use]
+    pub fn caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = caption.into();
+        self
+    }
+
+    /// Colour every cell drawn as `c`.
+    #[must_use]
+    pub fn paint(mut self, c: char, colour: Colour) -> Self {
+        self.palette.push((c, colour));
+        self
+    }
+
+    /// Colour the cell at `p`, taking precedence over [`Frame::paint`].
+    #[must_use]
+    pub fn colour(mut self, p: Point, colour: Colour) -> Self {
+        self.colours.insert(p, colour);
+        self
+    }
+
+    #[must_use]
+    pub fn colour_all(mut self, points: impl IntoIterator<Item = Point>, colour: Colour) -> Self {
+        self.colours.extend(points.into_iter().map(|p| (p, colour)));
+        self
+    }
+
+    /// Draw the cell at `p` in reverse video, e.g. the position of a robot.
+    #[must_use]
+    pub fn highlight(mut self, p: Point) -> Self {
+        self.highlights.insert(p);
+        self
+    }
+
+    #[must_use]
+    pub fn highlight_all(mut self, points: impl IntoIterator<Item = Point>) -> Self {
+        self.highlights.extend(points);
+        self
+    }
+
+    /// Colour set for the cell at `p` with [`Frame::colour`].
+    fn point_colour(&self, p: Point) -> Option<Colour> {
+        self.colours.get(&p).copied()
+    }
+
+    /// Colour set for cells drawn as `c` with [`Frame::paint`].
+    fn char_colour(&self, c: char) -> Option<Colour> {
+        self.palette
+            .iter()
+            .rev()
+            .find(|&&(d, _)| d == c)
+            .map(|&(_, colour)| colour)
+    }
+
+    fn is_highlighted(&self, p: Point) -> bool {
+        self.highlights.contains(&p)
+    }
+
+    fn style(&self, p: Point, c: char) -> String {
+        let colour = self.point_colour(p).or_else(|| self.char_colour(c));
+
+        let mut style = colour.map_or("", Colour::code).to_string();
+        if self.is_highlighted(p) {
+            style.push_str(ANSI_REVERSE);
+        }
+        style
+    }
+
+    /// The rows with ANSI colours, cropped to `width` columns and `height` rows.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    #[must_use]
+    pub fn render(&self, width: usize, height: usize) -> Vec<String> {
+        self.rows
+            .iter()
+            .take(height)
+            .enumerate()
+            .map(|(y, row)| {
+                let mut line = String::new();
+                let mut current = String::new();
+                for (x, c) in row.chars().take(width).enumerate() {
+                    let style = self.style(point2(x as i32, y as i32), c);
+                    if style != current {
+                        if !current.is_empty() {
+                            line.push_str(ANSI_RESET);
+                        }
+                        line.push_str(&style);
+                        current = style;
+                    }
+                    line.push(c);
+                }
+                if !current.is_empty() {
+                    line.push_str(ANSI_RESET);
+                }
+                line
+            })
+            .collect()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Record a frame. `f` is only called when the `visualize` feature is enabled.
+#[inline]
+pub fn frame(f: impl FnOnce() -> Frame) {
+    #[cfg(feature = "visualize")]
+    recorder::record(f());
+    #[cfg(not(feature = "visualize"))]
+    let _ = f;
+}
+
+/// Remove and return the frames recorded so far.
+#[must_use]
+pub fn take_frames() -> Vec<Frame> {
+    #[cfg(feature = "visualize")]
+    return recorder::take();
+    #[cfg(not(feature = "visualize"))]
+    vec![]
+}
+
+#[cfg(feature = "visualize")]
+mod recorder {
+    use std::sync::Mutex;
+
+    use super::{Frame, MAX_FRAMES};
+
+    static FRAMES: Mutex<Vec<Frame>> = Mutex::new(vec![]);
+
+    pub fn record(frame: Frame) {
+        let mut frames = FRAMES.lock().unwrap();
+        if frames.len() < MAX_FRAMES {
+            frames.push(frame);
+        }
+    }
+
+    pub fn take() -> Vec<Frame> {
+        std::mem::take(&mut FRAMES.lock().unwrap())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "visualize")]
+pub use player::play;
+
+#[cfg(not(feature = "visualize"))]
+pub fn play(_frames: &[Frame], _title: &str) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(feature = "visualize")]
+mod player {
+    use std::{
+        io::{self, stdout, Write},
+        time::Duration,
+    };
+
+    use crossterm::{
+        cursor::{Hide, MoveTo, Show},
+        event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+        execute, queue,
+        terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+    };
+
+    use super::{Frame, MAX_FRAMES};
+    use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+    const SPEEDS: [u32; 9] = [1, 2, 5, 10, 20, 30, 60, 120, 240];
+    const DEFAULT_SPEED: usize = 4;
+    const HELP: &str = "space play/pause  ←/→ step  ↑/↓ speed  home/end jump  q quit";
+
+    struct Player<'a> {
+        frames: &'a [Frame],
+        title: &'a str,
+        index: usize,
+        speed: usize,
+        playing: bool,
+    }
+
+    /// Play `frames` in the alternate screen until the user quits.
+    pub fn play(frames: &[Frame], title: &str) -> io::Result<()> {
+        let mut player = Player {
+            frames,
+            title,
+            index: 0,
+            speed: DEFAULT_SPEED,
+            playing: true,
+        };
+
+        terminal::enable_raw_mode()?;
+        execute!(stdout(), EnterAlternateScreen, Hide)?;
+        let result = player.run();
+        execute!(stdout(), Show, LeaveAlternateScreen)?;
+        terminal::disable_raw_mode()?;
+        result
+    }
+
+    impl Player<'_> {
+        fn run(&mut self) -> io::Result<()> {
+            loop {
+                self.draw()?;
+
+                let delay = Duration::from_secs(1) / SPEEDS[self.speed];
+                if self.playing && !event::poll(delay)? {
+                    if self.index + 1 < self.frames.len() {
+                        self.index += 1;
+                    } else {
+                        self.playing = false;
+                    }
+                    continue;
+                }
+
+                if let Event::Key(key) = event::read()? {
+                    if !self.handle(key) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+
+        /// Apply a key press, returns `false` to quit.
+        fn handle(&mut self, key: KeyEvent) -> bool {
+            if key.kind != KeyEventKind::Press {
+                return true;
+            }
+
+            let last = self.frames.len() - 1;
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return false,
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return false
+                }
+                KeyCode::Char(' ') => {
+                    if self.index == last {
+                        self.index = 0;
+                    }
+                    self.playing = !self.playing;
+                }
+                KeyCode::Right | KeyCode::Char('l') => {
+                    self.playing = false;
+                    self.index = (self.index + 1).min(last);
+                }
+                KeyCode::Left | KeyCode::Char('h') => {
+                    self.playing = false;
+                    self.index = self.index.saturating_sub(1);
+                }
+                KeyCode::Up | KeyCode::Char('+') => {
+                    self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+                }
+                KeyCode::Down | KeyCode::Char('-') => self.speed = self.speed.saturating_sub(1),
+                KeyCode::Home => self.index = 0,
+                KeyCode::End => self.index = last,
+                _ => {}
+            }
+            true
+        }
+
+        fn draw(&self) -> io::Result<()> {
+            let (width, height) = terminal::size()?;
+            let frame = &self.frames[self.index];
+            let mut out = stdout().lock();
+
+            let state = if self.playing { "playing" } else { "paused" };
+            let dropped = if self.frames.len() == MAX_FRAMES {
+                " (limit reached)"
+            } else {
+                ""
+            };
+            let status = format!(
+                "{ANSI_BOLD}{}{ANSI_RESET}  frame {}/{}{dropped}  {} fps  {state}  {}",
+                self.title,
+                self.index + 1,
+                self.frames.len(),
+                SPEEDS[self.speed],
+                frame.caption,
+            );
+
+            queue!(out, MoveTo(0, 0))?;
+            let rows = frame.render(width.into(), usize::from(height).saturating_sub(3));
+            for line in [status, String::new()].into_iter().chain(rows) {
+                queue!(out, Clear(ClearType::UntilNewLine))?;
+                write!(out, "{line}\r\n")?;
+            }
+            queue!(
+                out,
+                Clear(ClearType::FromCursorDown),
+                MoveTo(0, height.saturating_sub(1))
+            )?;
+            write!(out, "{HELP}")?;
+            out.flush()
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use lina::point2;
+
+    use super::{frame, take_frames, Colour, Frame};
+    use crate::grid::Grid;
+    use crate::template::ANSI_RESET;
+
+    #[test]
+    fn renders_colours_and_highlights() {
+        let grid = Grid::read("#.\n.#", |c| c);
+        let frame = Frame::chars(&grid)
+            .paint('#', Colour::Grey)
+            .colour(point2(1, 1), Colour::Red)
+            .highlight(point2(1, 0));
+
+        assert_eq!(frame.rows, vec!["#.", ".#"]);
+        assert_eq!(
+            frame.render(80, 80),
+            vec![
+                format!("\x1b[90m#{ANSI_RESET}\x1b[7m.{ANSI_RESET}"),
+                format!(".\x1b[31m#{ANSI_RESET}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn crops_to_terminal() {
+        let grid = Grid::new_with_dimensions(lina::vec2(4, 3), |p| p.x + p.y);
+        let frame = Frame::new(&grid, |&x| char::from_digit(x as u32, 10).unwrap());
+        assert_eq!(frame.render(2, 2), vec!["01", "12"]);
+    }
+
+    #[cfg(not(feature = "visualize"))]
+    #[test]
+    fn compiles_hooks_away() {
+        frame(|| unreachable!("frames are only built with the visualize feature"));
+        assert!(take_frames().is_empty());
+    }
+}